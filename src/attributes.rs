@@ -0,0 +1,56 @@
+use serde_json::{Map, Value as JsonValue};
+
+/// Attributes defined by the specification for schemas and record fields;
+/// any other attribute is custom.
+const STANDARD: [&str; 15] = [
+    "type",
+    "name",
+    "namespace",
+    "doc",
+    "aliases",
+    "fields",
+    "symbols",
+    "items",
+    "values",
+    "size",
+    "default",
+    "order",
+    "logicalType",
+    "precision",
+    "scale",
+];
+
+/// The custom attributes of a schema or record field definition.
+pub fn custom(object: &Map<String, JsonValue>) -> Map<String, JsonValue> {
+    object
+        .iter()
+        .filter(|(key, _)| !STANDARD.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// The definition of the record field at the dotted `path` from `json`,
+/// arrays, maps and unions being transparent.
+pub fn field<'a>(json: &'a JsonValue, path: &[&str]) -> Option<&'a Map<String, JsonValue>> {
+    let (name, rest) = path.split_first()?;
+    match json {
+        JsonValue::Array(variants) => variants.iter().find_map(|variant| field(variant, path)),
+        JsonValue::Object(object) => {
+            if let Some(JsonValue::Array(fields)) = object.get("fields") {
+                let found = fields
+                    .iter()
+                    .filter_map(JsonValue::as_object)
+                    .find(|field| field.get("name").and_then(JsonValue::as_str) == Some(*name))?;
+                return match rest {
+                    [] => Some(found),
+                    _ => field(found.get("type")?, rest),
+                };
+            }
+            ["items", "values", "type"]
+                .iter()
+                .filter_map(|key| object.get(*key))
+                .find_map(|inner| field(inner, path))
+        }
+        _ => None,
+    }
+}
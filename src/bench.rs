@@ -0,0 +1,120 @@
+use std::time::{Duration, Instant};
+
+use avro_rs::types::Value;
+use avro_rs::{from_avro_datum, to_avro_datum};
+use pyo3::exceptions;
+use pyo3::prelude::*;
+
+use crate::{to_avro_value, to_pyobject, Schema};
+
+/// Encode and decode throughputs measured by `bench`, in records per second.
+///
+/// `encode` and `decode` only measure the Avro binary encoding of values
+/// already converted from Python (plus a copy of each value, as avro-rs
+/// consumes them when encoding), `python_encode` and `python_decode`
+/// include the conversion from and to Python objects. `records` is the
+/// number of records processed by each measurement and `bytes` the size of
+/// the encoded sample records.
+#[pyclass]
+pub struct Bench {
+    #[pyo3(get)]
+    records: usize,
+    #[pyo3(get)]
+    bytes: usize,
+    #[pyo3(get)]
+    encode: f64,
+    #[pyo3(get)]
+    decode: f64,
+    #[pyo3(get)]
+    python_encode: f64,
+    #[pyo3(get)]
+    python_decode: f64,
+}
+
+#[pymethods]
+impl Bench {
+    fn __repr__(&self) -> String {
+        format!(
+            "Bench(records={}, bytes={}, encode={:.0}/s, decode={:.0}/s, python_encode={:.0}/s, python_decode={:.0}/s)",
+            self.records, self.bytes, self.encode, self.decode, self.python_encode, self.python_decode
+        )
+    }
+}
+
+/// Encode and decode `samples` with `schema` `iterations` times in each
+/// measurement.
+pub fn run(
+    py: Python,
+    schema: &Schema,
+    samples: &[PyObject],
+    iterations: usize,
+) -> PyResult<Bench> {
+    let values = samples
+        .iter()
+        .map(|sample| to_avro_value(py, sample, &schema.schema, &schema.config, None))
+        .collect::<PyResult<Vec<_>>>()?;
+    let encoded = values
+        .iter()
+        .map(|value| encode(schema, value.clone()))
+        .collect::<PyResult<Vec<_>>>()?;
+    let records = samples.len() * iterations;
+    let rate = |elapsed: Duration| records as f64 / elapsed.as_secs_f64();
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        for value in &values {
+            encode(schema, value.clone())?;
+        }
+    }
+    let encode_rate = rate(start.elapsed());
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        for bytes in &encoded {
+            decode(schema, bytes)?;
+        }
+    }
+    let decode_rate = rate(start.elapsed());
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        for sample in samples {
+            let value = to_avro_value(py, sample, &schema.schema, &schema.config, None)?;
+            encode(schema, value)?;
+        }
+    }
+    let python_encode = rate(start.elapsed());
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        for bytes in &encoded {
+            to_pyobject(
+                py,
+                decode(schema, bytes)?,
+                &schema.schema,
+                &schema.config,
+                None,
+            )?;
+        }
+    }
+    let python_decode = rate(start.elapsed());
+
+    Ok(Bench {
+        records,
+        bytes: encoded.iter().map(Vec::len).sum(),
+        encode: encode_rate,
+        decode: decode_rate,
+        python_encode,
+        python_decode,
+    })
+}
+
+fn encode(schema: &Schema, value: Value) -> PyResult<Vec<u8>> {
+    to_avro_datum(&schema.schema, value)
+        .map_err(|e| PyErr::new::<exceptions::ValueError, _>(format!("{}", e.as_fail())))
+}
+
+fn decode(schema: &Schema, mut bytes: &[u8]) -> PyResult<Value> {
+    from_avro_datum(&schema.schema, &mut bytes, None)
+        .map_err(|e| PyErr::new::<exceptions::ValueError, _>(format!("{}", e.as_fail())))
+}
@@ -0,0 +1,117 @@
+use avro_rs::types::Value;
+use avro_rs::Schema as SchemaRs;
+use std::os::raw::{c_int, c_void};
+
+use pyo3::buffer::{Element, PyBuffer};
+use pyo3::class::buffer::PyBufferProtocol;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use pyo3::{ffi, AsPyPointer};
+
+use crate::ArrayMode;
+
+fn extract_buffer<T: Element>(py: Python, datum: &PyObject) -> PyResult<Option<Vec<T>>> {
+    match PyBuffer::<T>::get(datum.as_ref(py)) {
+        Ok(buffer) if buffer.dimensions() == 1 => Ok(Some(buffer.to_vec(py)?)),
+        _ => Ok(None),
+    }
+}
+
+/// Read the items of an array of numeric `schema` straight from an object
+/// exposing a one-dimensional buffer of the matching type (`array.array`,
+/// numpy arrays, ...), without going through Python objects for each item.
+pub fn from_buffer(
+    py: Python,
+    datum: &PyObject,
+    schema: &SchemaRs,
+) -> PyResult<Option<Vec<Value>>> {
+    let items = match schema {
+        SchemaRs::Int => extract_buffer::<i32>(py, datum)?
+            .map(|items| items.into_iter().map(Value::Int).collect()),
+        SchemaRs::Long => extract_buffer::<i64>(py, datum)?
+            .map(|items| items.into_iter().map(Value::Long).collect()),
+        SchemaRs::Float => extract_buffer::<f32>(py, datum)?
+            .map(|items| items.into_iter().map(Value::Float).collect()),
+        SchemaRs::Double => extract_buffer::<f64>(py, datum)?
+            .map(|items| items.into_iter().map(Value::Double).collect()),
+        _ => None,
+    };
+    Ok(items)
+}
+
+/// Build an `array.array` (or numpy array) out of decoded items of a numeric
+/// `schema`. Returns `None` for other item types.
+pub fn to_array(
+    py: Python,
+    items: &[Value],
+    schema: &SchemaRs,
+    mode: ArrayMode,
+) -> PyResult<Option<PyObject>> {
+    let (typecode, size) = match schema {
+        SchemaRs::Int => ("i", 4),
+        SchemaRs::Long => ("q", 8),
+        SchemaRs::Float => ("f", 4),
+        SchemaRs::Double => ("d", 8),
+        _ => return Ok(None),
+    };
+
+    let mut bytes = Vec::with_capacity(items.len() * size);
+    for item in items {
+        match item {
+            Value::Int(n) => bytes.extend_from_slice(&n.to_ne_bytes()),
+            Value::Long(n) => bytes.extend_from_slice(&n.to_ne_bytes()),
+            Value::Float(x) => bytes.extend_from_slice(&x.to_ne_bytes()),
+            Value::Double(x) => bytes.extend_from_slice(&x.to_ne_bytes()),
+            _ => return Ok(None),
+        }
+    }
+
+    let array = py
+        .import("array")?
+        .call1("array", (typecode, PyBytes::new(py, &bytes)))?;
+    match mode {
+        ArrayMode::Numpy => Ok(Some(
+            py.import("numpy")?
+                .call1("asarray", (array,))?
+                .to_object(py),
+        )),
+        _ => Ok(Some(array.to_object(py))),
+    }
+}
+
+/// Read-only buffer over an encoding, returned by `Schema.write_buffer`.
+/// The bytes stay in the Rust allocation they were encoded into and are
+/// exposed through the buffer protocol instead of copied into `bytes`.
+#[pyclass]
+pub struct EncodedBuffer {
+    bytes: Vec<u8>,
+}
+
+impl EncodedBuffer {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        EncodedBuffer { bytes }
+    }
+}
+
+#[pyproto]
+impl PyBufferProtocol for EncodedBuffer {
+    fn bf_getbuffer(slf: PyRefMut<Self>, view: *mut ffi::Py_buffer, flags: c_int) -> PyResult<()> {
+        // The encoding is never mutated, so the pointer stays valid for as
+        // long as the view keeps a reference to the object.
+        let filled = unsafe {
+            ffi::PyBuffer_FillInfo(
+                view,
+                slf.as_ptr(),
+                slf.bytes.as_ptr() as *mut c_void,
+                slf.bytes.len() as ffi::Py_ssize_t,
+                1,
+                flags,
+            )
+        };
+        if filled == 0 {
+            Ok(())
+        } else {
+            Err(PyErr::fetch(slf.py()))
+        }
+    }
+}
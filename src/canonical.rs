@@ -0,0 +1,127 @@
+use std::collections::HashSet;
+
+use serde_json::{Map, Value as JsonValue};
+
+use crate::resolver::{named_type, PRIMITIVES};
+
+/// Attributes kept by the Parsing Canonical Form, in their canonical order.
+const ATTRIBUTES: [&str; 7] = [
+    "name", "type", "fields", "symbols", "items", "values", "size",
+];
+
+/// The [Parsing Canonical Form](https://avro.apache.org/docs/current/spec.html#Parsing+Canonical+Form+for+Schemas)
+/// of the schema `json`, from which fingerprints are computed.
+///
+/// avro-rs 0.10 `canonical_form` neither qualifies names with inherited
+/// namespaces nor refers to named types already defined by name, so its
+/// fingerprints differ from other implementations'.
+pub fn canonical_form(json: &JsonValue) -> String {
+    let mut out = String::new();
+    write(json, None, &mut HashSet::new(), &mut out);
+    out
+}
+
+fn write(
+    json: &JsonValue,
+    namespace: Option<&str>,
+    defined: &mut HashSet<String>,
+    out: &mut String,
+) {
+    match json {
+        JsonValue::String(name) => write_name(name, namespace, out),
+        JsonValue::Array(variants) => {
+            out.push('[');
+            for (i, variant) in variants.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write(variant, namespace, defined, out);
+            }
+            out.push(']');
+        }
+        JsonValue::Object(object) => write_object(object, namespace, defined, out),
+        _ => out.push_str(&json.to_string()),
+    }
+}
+
+fn write_name(name: &str, namespace: Option<&str>, out: &mut String) {
+    let fullname = match namespace {
+        Some(namespace) if !name.contains('.') && !PRIMITIVES.contains(&name) => {
+            format!("{}.{}", namespace, name)
+        }
+        _ => name.to_string(),
+    };
+    out.push_str(&JsonValue::String(fullname).to_string());
+}
+
+fn write_object(
+    object: &Map<String, JsonValue>,
+    namespace: Option<&str>,
+    defined: &mut HashSet<String>,
+    out: &mut String,
+) {
+    let (fullname, namespace) = match named_type(object, namespace) {
+        Some((fullname, namespace)) => (Some(fullname), namespace),
+        None => (None, namespace.map(|namespace| namespace.to_string())),
+    };
+    if let Some(fullname) = &fullname {
+        // Named types are defined once, and referred to by name afterwards.
+        if !defined.insert(fullname.clone()) {
+            return write_name(fullname, None, out);
+        }
+    }
+    let kind = object.get("type");
+    if fullname.is_none() && !kind.is_some_and(JsonValue::is_string) {
+        // `{"type": [...]}` and `{"type": {...}}` wrap their type.
+        if let Some(kind) = kind {
+            return write(kind, namespace.as_deref(), defined, out);
+        }
+    }
+    if let Some(JsonValue::String(kind)) = kind {
+        if PRIMITIVES.contains(&kind.as_str()) {
+            return write_name(kind, None, out);
+        }
+    }
+
+    out.push('{');
+    let mut first = true;
+    for attribute in ATTRIBUTES.iter() {
+        let value = match object.get(*attribute) {
+            Some(value) => value,
+            None => continue,
+        };
+        if !first {
+            out.push(',');
+        }
+        first = false;
+        out.push_str(&JsonValue::String(attribute.to_string()).to_string());
+        out.push(':');
+        match *attribute {
+            "name" => write_name(fullname.as_deref().unwrap_or_default(), None, out),
+            "fields" => write_fields(value, namespace.as_deref(), defined, out),
+            "items" | "values" => write(value, namespace.as_deref(), defined, out),
+            _ => out.push_str(&value.to_string()),
+        }
+    }
+    out.push('}');
+}
+
+fn write_fields(
+    fields: &JsonValue,
+    namespace: Option<&str>,
+    defined: &mut HashSet<String>,
+    out: &mut String,
+) {
+    out.push('[');
+    for (i, field) in fields.as_array().into_iter().flatten().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"name\":");
+        out.push_str(&field["name"].to_string());
+        out.push_str(",\"type\":");
+        write(&field["type"], namespace, defined, out);
+        out.push('}');
+    }
+    out.push(']');
+}
@@ -0,0 +1,106 @@
+use std::str::FromStr;
+
+use avro_rs::types::Value;
+use pyo3::exceptions;
+use pyo3::prelude::*;
+
+use crate::logical;
+
+/// Conversion of the decoded values of a field to another Python type,
+/// for legacy schemas declaring fields with the wrong type.
+#[derive(Clone, Copy)]
+pub enum Coercion {
+    /// `string->int`
+    Int,
+    /// `string->float`
+    Float,
+    /// `int->datetime(unit)` or `long->datetime(unit)`, with the number of
+    /// microseconds of the unit (`s`, `ms` or `us`).
+    Datetime(i64),
+}
+
+impl FromStr for Coercion {
+    type Err = PyErr;
+
+    fn from_str(name: &str) -> PyResult<Self> {
+        match name {
+            "string->int" => Ok(Coercion::Int),
+            "string->float" => Ok(Coercion::Float),
+            "int->datetime(s)" | "long->datetime(s)" => Ok(Coercion::Datetime(1_000_000)),
+            "int->datetime(ms)" | "long->datetime(ms)" => Ok(Coercion::Datetime(1_000)),
+            "int->datetime(us)" | "long->datetime(us)" => Ok(Coercion::Datetime(1)),
+            _ => Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                "Unknown coercion: {}",
+                name
+            ))),
+        }
+    }
+}
+
+impl Coercion {
+    /// The coerced Python value of the `field` value `datum`, `None` if the
+    /// coercion does not apply to it (e.g. `null`). Strings read as bytes,
+    /// when `decode_errors` is set, are coerced if they are valid UTF-8.
+    pub fn apply(self, py: Python, datum: &Value, field: &str) -> Option<PyResult<PyObject>> {
+        match (self, datum) {
+            (_, Value::Union(inner)) => self.apply(py, inner, field),
+            (Coercion::Int, Value::String(string)) => Some(parse::<i64>(py, string, field, "int")),
+            (Coercion::Float, Value::String(string)) => {
+                Some(parse::<f64>(py, string, field, "float"))
+            }
+            (Coercion::Int, Value::Bytes(bytes)) => {
+                Some(parse_bytes::<i64>(py, bytes, field, "int"))
+            }
+            (Coercion::Float, Value::Bytes(bytes)) => {
+                Some(parse_bytes::<f64>(py, bytes, field, "float"))
+            }
+            (Coercion::Datetime(unit), Value::Int(n)) => {
+                Some(datetime(py, i64::from(*n), unit, field))
+            }
+            (Coercion::Datetime(unit), Value::Long(n)) => Some(datetime(py, *n, unit, field)),
+            _ => None,
+        }
+    }
+}
+
+fn datetime(py: Python, n: i64, unit: i64, field: &str) -> PyResult<PyObject> {
+    match n.checked_mul(unit) {
+        Some(micros) => logical::to_datetime(py, micros),
+        None => Err(PyErr::new::<exceptions::ValueError, _>(format!(
+            "Cannot coerce {} of field {} to datetime",
+            n, field
+        ))),
+    }
+}
+
+fn parse_bytes<T: FromStr + ToPyObject>(
+    py: Python,
+    bytes: &[u8],
+    field: &str,
+    kind: &str,
+) -> PyResult<PyObject> {
+    match std::str::from_utf8(bytes) {
+        Ok(string) => parse::<T>(py, string, field, kind),
+        Err(_) => Err(PyErr::new::<exceptions::ValueError, _>(format!(
+            "Cannot coerce {:?} of field {} to {}",
+            String::from_utf8_lossy(bytes),
+            field,
+            kind
+        ))),
+    }
+}
+
+fn parse<T: FromStr + ToPyObject>(
+    py: Python,
+    string: &str,
+    field: &str,
+    kind: &str,
+) -> PyResult<PyObject> {
+    match string.trim().parse::<T>() {
+        Ok(value) => Ok(value.to_object(py)),
+        Err(_) => Err(PyErr::new::<exceptions::ValueError, _>(format!(
+            "Cannot coerce '{}' of field {} to {}",
+            string, field, kind
+        ))),
+    }
+}
@@ -0,0 +1,61 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use avro_rs::schema_compatibility::SchemaCompatibility;
+use avro_rs::Reader;
+use pyo3::exceptions;
+use pyo3::prelude::*;
+
+use crate::canonical::canonical_form;
+use crate::Schema;
+
+/// Result of `check_file_schema`.
+///
+/// `writer_schema` is the Parsing Canonical Form of the schema found in the file
+/// header, `same_schema` whether it is the expected schema, and
+/// `compatible` whether both schemas are compatible in `mode`.
+#[pyclass]
+pub struct FileSchemaReport {
+    #[pyo3(get)]
+    mode: String,
+    #[pyo3(get)]
+    compatible: bool,
+    #[pyo3(get)]
+    same_schema: bool,
+    #[pyo3(get)]
+    writer_schema: String,
+}
+
+/// Compare the writer schema of the container file at `path`, reading its
+/// header only, with `expected` in `mode`: `BACKWARD` (data of the file is
+/// readable with `expected`), `FORWARD` (data written with `expected` is
+/// readable with the writer schema) or `FULL` (both).
+pub fn check(path: &Path, expected: &Schema, mode: &str) -> PyResult<FileSchemaReport> {
+    if !["BACKWARD", "FORWARD", "FULL"].contains(&mode) {
+        return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+            "Unknown compatibility mode: {}",
+            mode
+        )));
+    }
+    let file = File::open(path)?;
+    let reader = Reader::new(BufReader::new(file)).map_err(|e| {
+        PyErr::new::<exceptions::ValueError, _>(format!("{}: {}", path.display(), e.as_fail()))
+    })?;
+    let writer = reader.writer_schema();
+    let compatible = match mode {
+        "BACKWARD" => SchemaCompatibility::can_read(writer, &expected.schema),
+        "FORWARD" => SchemaCompatibility::can_read(&expected.schema, writer),
+        _ => SchemaCompatibility::mutual_read(writer, &expected.schema),
+    };
+    let writer_json = serde_json::to_value(writer).map_err(|e| {
+        PyErr::new::<exceptions::ValueError, _>(format!("{}: {}", path.display(), e))
+    })?;
+    let writer_schema = canonical_form(&writer_json);
+    Ok(FileSchemaReport {
+        mode: mode.to_string(),
+        compatible,
+        same_schema: writer_schema == canonical_form(&expected.json),
+        writer_schema,
+    })
+}
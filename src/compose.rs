@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value as JsonValue};
+
+use crate::errors::ParseError;
+use crate::resolver::named_type;
+use crate::validate::check_default;
+
+/// The fields of the record at the dotted `path` of fields from `json`,
+/// arrays, maps and unions being transparent. An empty path designates the
+/// top-level record.
+fn record_fields_mut<'a>(json: &'a mut JsonValue, path: &[&str]) -> Option<&'a mut Vec<JsonValue>> {
+    match json {
+        JsonValue::Array(variants) => variants
+            .iter_mut()
+            .find(|variant| fields(variant, path).is_some())
+            .and_then(|variant| record_fields_mut(variant, path)),
+        JsonValue::Object(object) => {
+            if object.contains_key("fields") {
+                let (name, rest) = match path.split_first() {
+                    Some(split) => split,
+                    None => return object.get_mut("fields")?.as_array_mut(),
+                };
+                let field = object
+                    .get_mut("fields")?
+                    .as_array_mut()?
+                    .iter_mut()
+                    .find(|field| field.get("name").and_then(JsonValue::as_str) == Some(*name))?;
+                return record_fields_mut(field.get_mut("type")?, rest);
+            }
+            let key = ["items", "values", "type"].iter().find(|key| {
+                object
+                    .get(**key)
+                    .is_some_and(|inner| fields(inner, path).is_some())
+            })?;
+            record_fields_mut(object.get_mut(*key)?, path)
+        }
+        _ => None,
+    }
+}
+
+/// The record at the dotted `path` of fields from `json`, arrays, maps and
+/// unions being transparent. An empty path designates the top-level record.
+fn record<'a>(json: &'a JsonValue, path: &[&str]) -> Option<&'a Map<String, JsonValue>> {
+    match json {
+        JsonValue::Array(variants) => variants.iter().find_map(|variant| record(variant, path)),
+        JsonValue::Object(object) => {
+            if let Some(JsonValue::Array(record_fields)) = object.get("fields") {
+                return match path.split_first() {
+                    None => Some(object),
+                    Some((name, rest)) => record_fields
+                        .iter()
+                        .find(|field| field.get("name").and_then(JsonValue::as_str) == Some(*name))
+                        .and_then(|field| record(field.get("type")?, rest)),
+                };
+            }
+            ["items", "values", "type"]
+                .iter()
+                .filter_map(|key| object.get(*key))
+                .find_map(|inner| record(inner, path))
+        }
+        _ => None,
+    }
+}
+
+fn fields<'a>(json: &'a JsonValue, path: &[&str]) -> Option<&'a Vec<JsonValue>> {
+    record(json, path)?.get("fields")?.as_array()
+}
+
+/// Apply `edit` to the fields of every definition of the record `fullname`,
+/// as a named type used at several paths is inlined at each of them.
+fn edit_definitions(
+    json: &mut JsonValue,
+    fullname: &str,
+    edit: &mut dyn FnMut(&mut Vec<JsonValue>),
+) {
+    match json {
+        JsonValue::Array(variants) => {
+            for variant in variants {
+                edit_definitions(variant, fullname, edit);
+            }
+        }
+        JsonValue::Object(object) => {
+            let named = named_type(object, None).map(|(name, _)| name);
+            if let Some(JsonValue::Array(fields)) = object.get_mut("fields") {
+                if named.as_deref() == Some(fullname) {
+                    edit(fields);
+                }
+                for field in fields.iter_mut() {
+                    if let Some(inner) = field.get_mut("type") {
+                        edit_definitions(inner, fullname, edit);
+                    }
+                }
+            }
+            for key in &["items", "values", "type"] {
+                if let Some(inner) = object.get_mut(*key) {
+                    edit_definitions(inner, fullname, edit);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Full name of the record at `path`.
+fn record_name(json: &JsonValue, path: &[&str]) -> Option<String> {
+    named_type(record(json, path)?, None).map(|(name, _)| name)
+}
+
+/// Collect the named type definitions of `json` by full name, checking
+/// those already collected are defined identically.
+fn collect_definitions<'a>(
+    json: &'a JsonValue,
+    definitions: &mut HashMap<String, &'a JsonValue>,
+    check: bool,
+) -> Result<(), String> {
+    match json {
+        JsonValue::Array(variants) => {
+            for variant in variants {
+                collect_definitions(variant, definitions, check)?;
+            }
+        }
+        JsonValue::Object(object) => {
+            if let Some((fullname, _)) = named_type(object, None) {
+                match definitions.get(&fullname) {
+                    Some(known) if check && *known != json => {
+                        return Err(format!(
+                            "Named type {} is defined differently in both schemas",
+                            fullname
+                        ))
+                    }
+                    Some(_) => {}
+                    None => {
+                        definitions.insert(fullname, json);
+                    }
+                }
+            }
+            for field in object
+                .get("fields")
+                .and_then(JsonValue::as_array)
+                .into_iter()
+                .flatten()
+            {
+                if let Some(inner) = field.get("type") {
+                    collect_definitions(inner, definitions, check)?;
+                }
+            }
+            for key in &["items", "values", "type"] {
+                if let Some(inner) = object.get(*key) {
+                    collect_definitions(inner, definitions, check)?;
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Add `field` to the record at `path`, failing if a field of the same name
+/// exists. Every definition of the record is edited alike.
+pub fn add_field(
+    json: &mut JsonValue,
+    path: &[&str],
+    field: Map<String, JsonValue>,
+) -> Result<(), String> {
+    let name = field
+        .get("name")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let no_record = || format!("No record at {}", path.join("."));
+    let fields = fields(json, path).ok_or_else(no_record)?;
+    if fields
+        .iter()
+        .any(|existing| existing.get("name").and_then(JsonValue::as_str) == Some(&name))
+    {
+        return Err(format!("Field {} already exists", name));
+    }
+    let fullname = record_name(json, path).ok_or_else(no_record)?;
+    let field = JsonValue::Object(field);
+    edit_definitions(json, &fullname, &mut |fields| fields.push(field.clone()));
+    Ok(())
+}
+
+/// Remove the field at the dotted `path`, failing if there is none. Every
+/// definition of its record is edited alike.
+pub fn remove_field(json: &mut JsonValue, path: &[&str]) -> Result<(), String> {
+    let unknown = || format!("Unknown field path: {}", path.join("."));
+    let (name, parent) = path.split_last().ok_or_else(unknown)?;
+    let fields = fields(json, parent).ok_or_else(unknown)?;
+    if !fields
+        .iter()
+        .any(|field| field.get("name").and_then(JsonValue::as_str) == Some(*name))
+    {
+        return Err(unknown());
+    }
+    let fullname = record_name(json, parent).ok_or_else(unknown)?;
+    edit_definitions(json, &fullname, &mut |fields| {
+        fields.retain(|field| field.get("name").and_then(JsonValue::as_str) != Some(*name))
+    });
+    Ok(())
+}
+
+/// Add the fields of the top-level record of `other` to the one of `json`.
+/// Fields defined identically in both are kept once; fields of the same
+/// name with different definitions are a collision, as are named types
+/// defined differently. Added fields need a valid default, so data written
+/// with `json` stays readable.
+pub fn merge(json: &mut JsonValue, other: &JsonValue) -> Result<(), ParseError> {
+    let not_records = || ParseError::new("Can only merge records".to_string());
+    let others = fields(other, &[]).ok_or_else(not_records)?;
+    let mut definitions = HashMap::new();
+    for field in fields(json, &[]).ok_or_else(not_records)? {
+        collect_definitions(&field["type"], &mut definitions, false).map_err(ParseError::new)?;
+    }
+    for field in others {
+        collect_definitions(&field["type"], &mut definitions, true).map_err(ParseError::new)?;
+    }
+    let fields = record_fields_mut(json, &[]).ok_or_else(not_records)?;
+    for (i, field) in others.iter().enumerate() {
+        let name = field.get("name");
+        let display = name.and_then(JsonValue::as_str).unwrap_or_default();
+        match fields.iter().find(|existing| existing.get("name") == name) {
+            Some(existing) if existing == field => {}
+            Some(_) => {
+                return Err(ParseError::new(format!(
+                    "Field {} is defined differently in both schemas",
+                    display
+                )))
+            }
+            None => {
+                let path = format!("$.fields[{}]", i);
+                let object = field.as_object().ok_or_else(not_records)?;
+                if !object.contains_key("default") {
+                    return Err(
+                        ParseError::new(format!("Field {} needs a default", display)).at(&path),
+                    );
+                }
+                check_default(object, &path)?;
+                fields.push(field.clone());
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+/// A decoded value without a Python representation, returned when the
+/// schema was created with `unsupported="datum"`.
+///
+/// `kind` is the Avro (logical) type name of the value and `raw` its Avro
+/// binary encoding, or `None` if it could not be re-encoded.
+#[pyclass]
+pub struct Datum {
+    #[pyo3(get)]
+    kind: String,
+    #[pyo3(get)]
+    raw: Option<Py<PyBytes>>,
+}
+
+impl Datum {
+    pub fn new(py: Python, kind: String, raw: Option<Vec<u8>>) -> Self {
+        Datum {
+            kind,
+            raw: raw.map(|raw| PyBytes::new(py, &raw).into()),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use pyo3::exceptions;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::{log, to_pyobject, Schema};
+
+const SINGLE_OBJECT_MAGIC: [u8; 2] = [0xc3, 0x01];
+const REGISTRY_MAGIC: u8 = 0x00;
+
+/// Writer schema reference found in the header of a framed message.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SchemaRef {
+    /// Little-endian CRC-64-AVRO fingerprint (single object encoding).
+    Fingerprint([u8; 8]),
+    /// Schema registry identifier (Confluent wire format).
+    Id(u32),
+}
+
+impl SchemaRef {
+    pub fn to_object(self, py: Python) -> PyObject {
+        match self {
+            SchemaRef::Fingerprint(fingerprint) => PyBytes::new(py, &fingerprint).to_object(py),
+            SchemaRef::Id(id) => id.to_object(py),
+        }
+    }
+}
+
+impl fmt::Display for SchemaRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaRef::Fingerprint(fingerprint) => {
+                write!(f, "fingerprint ")?;
+                for byte in fingerprint.iter() {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+            SchemaRef::Id(id) => write!(f, "id {}", id),
+        }
+    }
+}
+
+pub fn split_header(message: &[u8]) -> PyResult<(SchemaRef, &[u8])> {
+    if message.len() >= 10 && message[..2] == SINGLE_OBJECT_MAGIC {
+        let mut fingerprint = [0; 8];
+        fingerprint.copy_from_slice(&message[2..10]);
+        Ok((SchemaRef::Fingerprint(fingerprint), &message[10..]))
+    } else if message.len() >= 5 && message[0] == REGISTRY_MAGIC {
+        let mut id = [0; 4];
+        id.copy_from_slice(&message[1..5]);
+        Ok((SchemaRef::Id(u32::from_be_bytes(id)), &message[5..]))
+    } else {
+        Err(PyErr::new::<exceptions::ValueError, _>(
+            "Message is neither single object encoded nor schema registry framed",
+        ))
+    }
+}
+
+/// Decoder for single object encoded and schema registry framed messages.
+///
+/// Writer schemas are looked up through `resolve`, called with the 8-byte
+/// fingerprint (`bytes`) or the registry id (`int`) found in the message
+/// header. Resolved schemas are cached for the lifetime of the decoder.
+#[pyclass]
+pub struct Decoder {
+    resolve: PyObject,
+    schemas: HashMap<SchemaRef, Py<Schema>>,
+}
+
+#[pymethods]
+impl Decoder {
+    #[new]
+    fn new(resolve: PyObject) -> Self {
+        Decoder {
+            resolve,
+            schemas: HashMap::new(),
+        }
+    }
+
+    fn read(&mut self, py: Python, message: &PyBytes) -> PyResult<PyObject> {
+        let (reference, payload) = split_header(message.as_bytes())?;
+        let schema = self.schema(py, reference)?;
+        let schema = schema.borrow(py);
+        let value = schema.decode_with(py, payload, schema.reader_schema(), None)?;
+        to_pyobject(py, value, &schema.schema, &schema.config, None)
+    }
+}
+
+impl Decoder {
+    fn schema(&mut self, py: Python, reference: SchemaRef) -> PyResult<Py<Schema>> {
+        if let Some(schema) = self.schemas.get(&reference) {
+            return Ok(schema.clone_ref(py));
+        }
+
+        log::log(py, log::DEBUG, || {
+            format!("Resolving writer schema with {}", reference)
+        });
+        let resolved = self.resolve.call1(py, (reference.to_object(py),))?;
+        if resolved.is_none(py) {
+            return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                "Unable to resolve writer schema with {}",
+                reference
+            )));
+        }
+        let schema = resolved.extract::<Py<Schema>>(py)?;
+        self.schemas.insert(reference, schema.clone_ref(py));
+        Ok(schema)
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+use pyo3::exceptions;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyTuple, PyType};
+use pyo3::AsPyPointer;
+use serde_json::{json, Map, Value as JsonValue};
+
+use crate::pydantic;
+
+/// Derives Avro schemas from annotated Python classes (dataclasses,
+/// `TypedDict`s and pydantic models), collecting the classes to register
+/// for decoding.
+pub struct Deriver<'p> {
+    py: Python<'p>,
+    builtins: &'p PyModule,
+    typing: &'p PyModule,
+    dataclasses: &'p PyModule,
+    datetime: &'p PyModule,
+    enum_type: &'p PyAny,
+    stack: Vec<String>,
+    pub types: HashMap<String, PyObject>,
+}
+
+impl<'p> Deriver<'p> {
+    pub fn new(py: Python<'p>) -> PyResult<Self> {
+        Ok(Deriver {
+            py,
+            builtins: py.import("builtins")?,
+            typing: py.import("typing")?,
+            dataclasses: py.import("dataclasses")?,
+            datetime: py.import("datetime")?,
+            enum_type: py.import("enum")?.getattr("Enum")?,
+            stack: Vec::new(),
+            types: HashMap::new(),
+        })
+    }
+
+    /// The record schema of `cls`, if it is a dataclass, a `TypedDict` or a
+    /// pydantic model.
+    pub fn record(&mut self, cls: &'p PyType) -> PyResult<JsonValue> {
+        let name = cls.getattr("__name__")?.extract::<String>()?;
+        let fields = match self.fields(cls)? {
+            Some(fields) => fields,
+            None => {
+                return Err(PyErr::new::<exceptions::TypeError, _>(format!(
+                    "Expected a dataclass, TypedDict or pydantic model, got {}",
+                    name
+                )))
+            }
+        };
+        if self.stack.contains(&name) {
+            return Err(PyErr::new::<exceptions::TypeError, _>(format!(
+                "Recursive type {} is not supported",
+                name
+            )));
+        }
+        self.stack.push(name.clone());
+
+        let mut schemas = Vec::with_capacity(fields.len());
+        for (field_name, annotation, default) in fields {
+            let mut field = Map::new();
+            field.insert("name".to_string(), JsonValue::String(field_name));
+            let mut schema = self.annotation(annotation)?;
+            if let Some(default) = default.and_then(default_value) {
+                order_union(&mut schema, &default);
+                field.insert("default".to_string(), default);
+            }
+            field.insert("type".to_string(), schema);
+            schemas.push(JsonValue::Object(field));
+        }
+
+        self.stack.pop();
+        // A TypedDict cannot be instantiated with keyword arguments into
+        // anything but a dict, so there is no point registering it.
+        if !cls.hasattr("__total__")? {
+            self.types.insert(name.clone(), cls.to_object(self.py));
+        }
+        Ok(json!({"type": "record", "name": name, "fields": schemas}))
+    }
+
+    /// Name, annotation and default of each field of `cls`, if it is a
+    /// record-like class. Pydantic fields are named by their alias.
+    #[allow(clippy::type_complexity)]
+    fn fields(
+        &self,
+        cls: &'p PyType,
+    ) -> PyResult<Option<Vec<(String, &'p PyAny, Option<&'p PyAny>)>>> {
+        let mut fields = Vec::new();
+        if pydantic::is_model(cls) {
+            for (name, info) in pydantic::model_fields(cls)? {
+                let alias = info.getattr("alias")?;
+                let name = if alias.is_none() { name } else { alias };
+                let default = if info.call_method0("is_required")?.extract::<bool>()? {
+                    None
+                } else {
+                    Some(info.getattr("default")?)
+                };
+                fields.push((name.extract()?, info.getattr("annotation")?, default));
+            }
+        } else if self
+            .dataclasses
+            .call1("is_dataclass", (cls,))?
+            .extract::<bool>()?
+        {
+            let hints = self.typing.call1("get_type_hints", (cls,))?;
+            let missing = self.dataclasses.getattr("MISSING")?;
+            for field in self.dataclasses.call1("fields", (cls,))?.iter()? {
+                let field = field?;
+                let name = field.getattr("name")?;
+                let default = Some(field.getattr("default")?).filter(|d| !is(d, missing));
+                fields.push((name.extract()?, hints.get_item(name)?, default));
+            }
+        } else if cls.hasattr("__total__")? {
+            let hints = self.typing.call1("get_type_hints", (cls,))?;
+            for name in hints.iter()? {
+                let name = name?;
+                fields.push((name.extract()?, hints.get_item(name)?, None));
+            }
+        } else {
+            return Ok(None);
+        }
+        Ok(Some(fields))
+    }
+
+    fn annotation(&mut self, annotation: &'p PyAny) -> PyResult<JsonValue> {
+        for (builtin, schema) in &[
+            ("bool", "boolean"),
+            ("int", "long"),
+            ("float", "double"),
+            ("str", "string"),
+            ("bytes", "bytes"),
+        ] {
+            if is(annotation, self.builtins.getattr(builtin)?) {
+                return Ok(JsonValue::String(schema.to_string()));
+            }
+        }
+        if annotation.is_none() || is(annotation, self.py.None().as_ref(self.py).get_type()) {
+            return Ok(JsonValue::String("null".to_string()));
+        }
+        // datetime is a subclass of date, so it is checked first.
+        if is(annotation, self.datetime.getattr("datetime")?) {
+            return Ok(json!({"type": "long", "logicalType": "timestamp-micros"}));
+        }
+        if is(annotation, self.datetime.getattr("date")?) {
+            return Ok(json!({"type": "int", "logicalType": "date"}));
+        }
+
+        // `typing` generics expose `__origin__` and `__args__`; `X | Y`
+        // unions only `__args__`.
+        let origin = annotation.getattr("__origin__").ok();
+        let args = match annotation.getattr("__args__") {
+            Ok(args) => args.cast_as::<PyTuple>()?.as_slice().to_vec(),
+            Err(_) => Vec::new(),
+        };
+        let union_type = self.py.import("types")?.getattr("UnionType").ok();
+        let is_union = match origin {
+            Some(origin) => is(origin, self.typing.getattr("Union")?),
+            None => union_type.is_some_and(|union_type| is(annotation.get_type(), union_type)),
+        };
+        if is_union {
+            return args
+                .into_iter()
+                .map(|arg| self.annotation(arg))
+                .collect::<PyResult<Vec<_>>>()
+                .map(JsonValue::Array);
+        }
+        if let Some(origin) = origin {
+            for sequence in &["list", "set", "frozenset"] {
+                if is(origin, self.builtins.getattr(sequence)?) && args.len() == 1 {
+                    let items = self.annotation(args[0])?;
+                    return Ok(json!({"type": "array", "items": items}));
+                }
+            }
+            if is(origin, self.builtins.getattr("dict")?)
+                && args.len() == 2
+                && is(args[0], self.builtins.getattr("str")?)
+            {
+                let values = self.annotation(args[1])?;
+                return Ok(json!({"type": "map", "values": values}));
+            }
+        }
+
+        if let Ok(cls) = annotation.cast_as::<PyType>() {
+            if self
+                .builtins
+                .call1("issubclass", (cls, self.enum_type))?
+                .extract::<bool>()?
+            {
+                return self.enumeration(cls);
+            }
+            if self.fields(cls)?.is_some() {
+                return self.record(cls);
+            }
+        }
+
+        Err(PyErr::new::<exceptions::TypeError, _>(format!(
+            "Unsupported annotation: {}",
+            annotation.repr()?
+        )))
+    }
+
+    /// The enum schema of an `enum.Enum` subclass, whose symbols are the member names.
+    fn enumeration(&mut self, cls: &'p PyType) -> PyResult<JsonValue> {
+        let name = cls.getattr("__name__")?.extract::<String>()?;
+        let symbols = cls
+            .getattr("__members__")?
+            .iter()?
+            .map(|symbol| symbol?.extract::<String>())
+            .collect::<PyResult<Vec<_>>>()?;
+        self.types.insert(name.clone(), cls.to_object(self.py));
+        Ok(json!({"type": "enum", "name": name, "symbols": symbols}))
+    }
+}
+
+fn is(a: &PyAny, b: &PyAny) -> bool {
+    a.as_ptr() == b.as_ptr()
+}
+
+/// The JSON of a field default, if it can be written in a schema.
+fn default_value(value: &PyAny) -> Option<JsonValue> {
+    if value.is_none() {
+        Some(JsonValue::Null)
+    } else if let Ok(value) = value.cast_as::<PyBool>() {
+        Some(JsonValue::Bool(value.is_true()))
+    } else if let Ok(value) = value.extract::<i64>() {
+        Some(JsonValue::from(value))
+    } else if let Ok(value) = value.extract::<f64>() {
+        Some(JsonValue::from(value))
+    } else if let Ok(symbol) = value.getattr("_name_") {
+        symbol.extract::<String>().ok().map(JsonValue::String)
+    } else if let Ok(value) = value.extract::<String>() {
+        Some(JsonValue::String(value))
+    } else {
+        None
+    }
+}
+
+/// Move `null` first in a union whose default is null, and last otherwise,
+/// as the default must match the first variant of a union.
+fn order_union(schema: &mut JsonValue, default: &JsonValue) {
+    if let JsonValue::Array(variants) = schema {
+        let null = JsonValue::String("null".to_string());
+        if let Some(index) = variants.iter().position(|variant| variant == &null) {
+            variants.remove(index);
+            match default {
+                JsonValue::Null => variants.insert(0, null),
+                _ => variants.push(null),
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+
+use pyo3::create_exception;
+use pyo3::exceptions;
+use pyo3::prelude::*;
+
+create_exception!(pyo3avro_rs, SchemaParseError, exceptions::ValueError);
+create_exception!(pyo3avro_rs, SerializationError, exceptions::TypeError);
+
+/// A `SerializationError` with `message`, chaining the Python error `cause`
+/// (if any) as its `__cause__`.
+pub fn serialization_error(py: Python, message: &str, cause: Option<PyErr>) -> PyErr {
+    let exception = match py.get_type::<SerializationError>().call1((message,)) {
+        Ok(exception) => exception,
+        Err(e) => return e,
+    };
+    if let Some(cause) = cause {
+        if let Err(e) = exception.setattr("__cause__", cause.to_object(py)) {
+            return e;
+        }
+    }
+    PyErr::from_instance(exception)
+}
+
+/// The `__cause__` of `error` when it is a `SerializationError` chaining
+/// one, as raised by a nested union failing on an error of the datum.
+pub fn chained_cause(py: Python, error: &PyErr) -> Option<PyErr> {
+    if !error.is_instance::<SerializationError>(py) {
+        return None;
+    }
+    let cause = error.to_object(py).getattr(py, "__cause__").ok()?;
+    if cause.is_none(py) {
+        None
+    } else {
+        Some(PyErr::from_instance(cause.as_ref(py)))
+    }
+}
+
+/// A schema parse failure, located as precisely as possible.
+///
+/// Raised to Python as `SchemaParseError`, with `file`, `path` (JSON path of
+/// the offending node, e.g. `$.fields[2].type`), `line`, `column` and
+/// `suggestion` attributes, each `None` when unknown.
+#[derive(Debug)]
+pub struct ParseError {
+    message: String,
+    // Boxed to keep `Result<_, ParseError>` small on the happy path
+    location: Box<Location>,
+}
+
+#[derive(Debug, Default)]
+struct Location {
+    file: Option<String>,
+    path: Option<String>,
+    line: Option<usize>,
+    column: Option<usize>,
+    suggestion: Option<String>,
+}
+
+impl ParseError {
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        ParseError {
+            message: message.into(),
+            location: Box::new(Location::default()),
+        }
+    }
+
+    pub fn at(mut self, path: &str) -> Self {
+        self.location.path = Some(path.to_string());
+        self
+    }
+
+    pub fn suggest(mut self, suggestion: Option<String>) -> Self {
+        self.location.suggestion = suggestion;
+        self
+    }
+
+    pub fn in_file(mut self, file: &str) -> Self {
+        self.location.file.get_or_insert_with(|| file.to_string());
+        self
+    }
+
+    pub fn into_pyerr(self, py: Python) -> PyErr {
+        let message = self.to_string();
+        let exception = match py.get_type::<SchemaParseError>().call1((message,)) {
+            Ok(exception) => exception,
+            Err(e) => return e,
+        };
+        let location = &self.location;
+        let attributes = [
+            ("file", location.file.to_object(py)),
+            ("path", location.path.to_object(py)),
+            ("line", location.line.to_object(py)),
+            ("column", location.column.to_object(py)),
+            ("suggestion", location.suggestion.to_object(py)),
+        ];
+        for (name, value) in attributes.iter() {
+            if let Err(e) = exception.setattr(*name, value) {
+                return e;
+            }
+        }
+        PyErr::from_instance(exception)
+    }
+}
+
+impl From<serde_json::Error> for ParseError {
+    fn from(e: serde_json::Error) -> Self {
+        let mut error = ParseError::new(e.to_string());
+        error.location.line = Some(e.line());
+        error.location.column = Some(e.column());
+        error
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let location = &self.location;
+        match (&location.file, location.line, location.column) {
+            (Some(file), Some(line), Some(column)) => write!(f, "{}:{}:{}: ", file, line, column)?,
+            (Some(file), _, _) => write!(f, "{}: ", file)?,
+            _ => {}
+        }
+        write!(f, "{}", self.message)?;
+        if let Some(path) = &location.path {
+            write!(f, " at {}", path)?;
+        }
+        if let Some(suggestion) = &location.suggestion {
+            write!(f, ", did you mean '{}'?", suggestion)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use avro_rs::Schema as SchemaRs;
+use pyo3::prelude::*;
+
+/// Path of record field names from the root schema, e.g. `user.email`.
+///
+/// Arrays, maps and unions are transparent: `orders.id` designates the `id`
+/// field of every record in the `orders` array. Built on the stack while
+/// walking values, so it costs nothing when no hook is registered.
+pub struct FieldPath<'a> {
+    parent: Option<&'a FieldPath<'a>>,
+    name: &'a str,
+}
+
+impl<'a> FieldPath<'a> {
+    pub fn child(parent: Option<&'a FieldPath<'a>>, name: &'a str) -> Self {
+        FieldPath { parent, name }
+    }
+
+    fn matches(&self, segments: &[String]) -> bool {
+        let mut current = Some(self);
+        for segment in segments.iter().rev() {
+            match current {
+                Some(path) if path.name == segment => current = path.parent,
+                _ => return false,
+            }
+        }
+        current.is_none()
+    }
+}
+
+/// Python callables run on the value of a field, keyed by field path. Also
+/// holds other per-field settings, such as coercions.
+pub struct Hooks<T = PyObject>(Vec<(Vec<String>, T)>);
+
+impl<T> Default for Hooks<T> {
+    fn default() -> Self {
+        Hooks(Vec::new())
+    }
+}
+
+impl<T> Hooks<T> {
+    pub fn insert(&mut self, path: &str, hook: T) {
+        let segments = path.split('.').map(str::to_string).collect::<Vec<_>>();
+        self.0.retain(|(existing, _)| existing != &segments);
+        self.0.push((segments, hook));
+    }
+
+    pub fn get(&self, path: &FieldPath) -> Option<&T> {
+        self.0
+            .iter()
+            .find(|(segments, _)| path.matches(segments))
+            .map(|(_, hook)| hook)
+    }
+}
+
+/// Whether the dotted `path` designates a field of a record reachable from `schema`.
+pub fn field_exists(schema: &SchemaRs, path: &[&str]) -> bool {
+    let (name, rest) = match path.split_first() {
+        Some(split) => split,
+        None => return true,
+    };
+    match schema {
+        SchemaRs::Array(inner) | SchemaRs::Map(inner) => field_exists(inner, path),
+        SchemaRs::Union(inner) => inner
+            .variants()
+            .iter()
+            .any(|variant| field_exists(variant, path)),
+        SchemaRs::Record { fields, .. } => fields
+            .iter()
+            .any(|field| field.name == *name && field_exists(&field.schema, rest)),
+        _ => false,
+    }
+}
@@ -1,179 +1,1297 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::time::Instant;
 
 use avro_rs::from_avro_datum;
+use avro_rs::schema::{RecordField, UnionSchema};
 use avro_rs::to_avro_datum;
 use avro_rs::types::Value;
 use avro_rs::Schema as SchemaRs;
 use pyo3::exceptions;
 use pyo3::prelude::*;
-use pyo3::types::{PyBytes, PyDict, PyList};
-use pyo3::PyDowncastError;
+use pyo3::types::{PyBool, PyBytes, PyDict, PyList, PyLong, PyString, PyTuple, PyType};
+use pyo3::wrap_pyfunction;
+use pyo3::{PyDowncastError, PyNativeType};
+use serde_json::Value as JsonValue;
 
+mod attributes;
+mod bench;
+mod buffer;
+mod canonical;
+mod coerce;
+mod compat;
+mod compose;
+mod datum;
+mod decoder;
+mod derive;
+mod errors;
+mod hooks;
+mod lint;
+mod log;
+mod logical;
+mod msgpack;
+mod paths;
+mod project;
+mod pydantic;
+mod rabin;
+mod resolver;
+mod schema_set;
+mod size;
+mod slots;
+mod stream;
+mod strings;
+mod validate;
+
+use bench::Bench;
+use buffer::EncodedBuffer;
+use compat::FileSchemaReport;
+use datum::Datum;
+use decoder::Decoder;
+use errors::{SchemaParseError, SerializationError};
+use hooks::{FieldPath, Hooks};
+use lint::Finding;
+use paths::FsPath;
+use schema_set::SchemaSet;
+use stream::{Cursor, DatumIter};
+
+/// How decoded arrays of numeric items are returned to Python.
+#[derive(Clone, Copy, PartialEq)]
+enum ArrayMode {
+    List,
+    Array,
+    Numpy,
+}
+
+/// What decoding does with values that have no Python representation.
+#[derive(Clone, Copy, PartialEq)]
+enum Unsupported {
+    Raise,
+    Warn,
+    Datum,
+}
+
+/// Conversion settings of a `Schema`, shared by the encode and decode paths.
+struct Config {
+    /// Python classes registered for record types, keyed by record full name.
+    types: HashMap<String, PyObject>,
+    arrays: ArrayMode,
+    /// Encoding used to write `str` values into `bytes` fields, rejected if unset.
+    bytes_encoding: Option<String>,
+    /// Encoding used to decode `bytes` fields into `str`, kept as `bytes` if unset.
+    decode_bytes: Option<String>,
+    /// Error handler used to write `str` values that are not encodable in
+    /// UTF-8 (lone surrogates), rejected if unset.
+    encode_errors: Option<String>,
+    /// Error handler used to read `string` values that are not valid UTF-8,
+    /// or `"bytes"` to return them as `bytes`. Rejected if unset.
+    decode_errors: Option<String>,
+    /// Whether decoded maps are returned with their keys sorted.
+    sort_maps: bool,
+    /// Whether union values are `(branch, value)` tuples.
+    union_branches: bool,
+    unsupported: Unsupported,
+    /// Python strings of the field names of each record type, keyed by
+    /// record full name, reused as keys of every decoded record and to look
+    /// fields up when encoding.
+    keys: HashMap<String, Vec<PyObject>>,
+    /// Index of each symbol of each enum type, keyed by enum full name.
+    symbols: HashMap<String, HashMap<String, i32>>,
+    /// Callable applied to every datum before encoding.
+    normalizer: Option<PyObject>,
+    /// Callables applied to field values before encoding.
+    encode_hooks: Hooks,
+    /// Callables applied to field values after decoding.
+    decode_hooks: Hooks,
+    /// Conversions of decoded field values, applied before decode hooks.
+    coercions: Hooks<coerce::Coercion>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            types: HashMap::new(),
+            arrays: ArrayMode::List,
+            bytes_encoding: Some("utf-8".to_string()),
+            decode_bytes: None,
+            encode_errors: None,
+            decode_errors: None,
+            sort_maps: false,
+            union_branches: false,
+            unsupported: Unsupported::Warn,
+            keys: HashMap::new(),
+            symbols: HashMap::new(),
+            normalizer: None,
+            encode_hooks: Hooks::default(),
+            decode_hooks: Hooks::default(),
+            coercions: Hooks::default(),
+        }
+    }
+}
 
 #[pyclass]
 struct Schema {
     schema: SchemaRs,
+    /// JSON the schema was parsed from, with named references inlined.
+    /// avro-rs drops custom attributes, so they are read from here.
+    json: JsonValue,
+    /// The schema with `string` types read as `bytes`, set when `string`
+    /// values that are not valid UTF-8 are accepted.
+    lenient: Option<SchemaRs>,
+    config: Config,
 }
 
 #[pymethods]
 impl Schema {
+    /// `arrays` selects how decoded arrays of `int`, `long`, `float` and
+    /// `double` items are returned: `"list"`, `"array"` (`array.array`) or
+    /// `"numpy"`.
+    ///
+    /// `bytes_encoding` is the encoding used to write `str` values into
+    /// `bytes` fields (`None` rejects them), and `decode_bytes` the encoding
+    /// used to read `bytes` fields back as `str` (`None` returns `bytes`).
+    ///
+    /// `encode_errors` is the error handler used to write `str` values with
+    /// lone surrogates: `"replace"` and `"ignore"` are useful, handlers
+    /// producing invalid UTF-8 are rejected as Avro strings must be valid
+    /// UTF-8. `decode_errors` is the error handler used to read `string`
+    /// values that are not valid UTF-8 (e.g. `"replace"` or
+    /// `"surrogateescape"`), or `"bytes"` to return them as `bytes`.
+    ///
+    /// Decoded records follow the field order of the schema. Decoded maps
+    /// have no defined key order unless `sort_maps` is set, in which case
+    /// their keys are sorted. Only the decoded key order is sorted: avro-rs
+    /// 0.10 holds maps in a `HashMap`, so the byte order of encoded maps is
+    /// not deterministic.
+    ///
+    /// `records` selects how decoded records are returned when no class is
+    /// registered for them: `"dict"`, or `"slots"` for instances of classes
+    /// generated with `__slots__`, which take about half the memory.
+    ///
+    /// `unions` selects how values of unions are returned: `"value"`, or
+    /// `"tuple"` for `(branch, value)` tuples naming the branch as
+    /// `read_union` does. In `"tuple"` mode, such tuples are also accepted
+    /// when encoding. avro-rs does not parse unions holding more than one
+    /// record, so the branch never has to tell records apart.
+    ///
+    /// `unsupported` selects what happens to decoded values without a Python
+    /// representation (such as `duration`): `"raise"` a `TypeError`, `"warn"`
+    /// with a `RuntimeWarning` and return `None`, or return a `Datum`.
+    ///
+    /// `check_defaults` rejects record field defaults that do not type-check
+    /// against the field schema (its first branch for unions), which would
+    /// otherwise only fail when resolving data against the schema.
     #[new]
-    fn new(input: &str) -> PyResult<Self> {
-        match SchemaRs::parse_str(&input) {
-            Ok(schema) => Ok(Schema { schema }),
-            Err(e) => Err(PyErr::new::<exceptions::ValueError, _>(format!(
-                "{}",
-                e.as_fail()
-            ))),
+    #[args(
+        arrays = "\"list\"",
+        bytes_encoding = "\"utf-8\"",
+        decode_bytes = "None",
+        encode_errors = "\"strict\"",
+        decode_errors = "\"strict\"",
+        sort_maps = "false",
+        records = "\"dict\"",
+        unions = "\"value\"",
+        unsupported = "\"warn\"",
+        check_defaults = "false"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        py: Python,
+        input: &str,
+        arrays: &str,
+        bytes_encoding: Option<&str>,
+        decode_bytes: Option<&str>,
+        encode_errors: &str,
+        decode_errors: &str,
+        sort_maps: bool,
+        records: &str,
+        unions: &str,
+        unsupported: &str,
+        check_defaults: bool,
+    ) -> PyResult<Self> {
+        let arrays = match arrays {
+            "list" => ArrayMode::List,
+            "array" => ArrayMode::Array,
+            "numpy" => ArrayMode::Numpy,
+            _ => {
+                return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                    "Unknown arrays mode: {}",
+                    arrays
+                )))
+            }
+        };
+        let unsupported = match unsupported {
+            "raise" => Unsupported::Raise,
+            "warn" => Unsupported::Warn,
+            "datum" => Unsupported::Datum,
+            _ => {
+                return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                    "Unknown unsupported policy: {}",
+                    unsupported
+                )))
+            }
+        };
+        if records != "dict" && records != "slots" {
+            return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                "Unknown records mode: {}",
+                records
+            )));
+        }
+        if unions != "value" && unions != "tuple" {
+            return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                "Unknown unions mode: {}",
+                unions
+            )));
+        }
+        let start = Instant::now();
+        let parsed = validate::parse_str(input).and_then(|(schema, json)| {
+            if check_defaults {
+                validate::check_defaults(&json, "$")?;
+            }
+            Ok((schema, json))
+        });
+        log::log(py, log::DEBUG, || match &parsed {
+            Ok(_) => format!("Parsed schema in {:?}", start.elapsed()),
+            Err(e) => format!("Failed to parse schema: {}", e),
+        });
+        match parsed {
+            Ok((schema, json)) => Ok(Schema::build(
+                py,
+                Config {
+                    types: match records {
+                        "slots" => slots::classes(py, &schema)?,
+                        _ => HashMap::new(),
+                    },
+                    arrays,
+                    bytes_encoding: bytes_encoding.map(str::to_string),
+                    decode_bytes: decode_bytes.map(str::to_string),
+                    encode_errors: Some(encode_errors)
+                        .filter(|errors| *errors != "strict")
+                        .map(str::to_string),
+                    decode_errors: Some(decode_errors)
+                        .filter(|errors| *errors != "strict")
+                        .map(str::to_string),
+                    sort_maps,
+                    union_branches: unions == "tuple",
+                    unsupported,
+                    ..Config::default()
+                },
+                schema,
+                json,
+            )),
+            Err(e) => Err(e.into_pyerr(py)),
         }
     }
 
-    fn write<'p>(&self, py: Python<'p>, datum: PyObject) -> PyResult<&'p PyBytes> {
-        let value = to_avro_value(py, &datum, &self.schema)?;
+    /// Parse the schema of an `.avsc` file. References to named types are
+    /// resolved against the `.avsc` files of the file's directory and of
+    /// `search_paths`.
+    #[staticmethod]
+    #[args(search_paths = "Vec::new()")]
+    fn from_file(py: Python, path: FsPath, search_paths: Vec<FsPath>) -> PyResult<Schema> {
+        let mut schemas = Schema::from_files(py, vec![path], search_paths)?;
+        Ok(schemas.remove(0))
+    }
 
-        match to_avro_datum(&self.schema, value) {
-            Ok(bytes) => Ok(PyBytes::new(py, &bytes)),
-            Err(e) => Err(PyErr::new::<exceptions::ValueError, _>(format!(
-                "{}",
-                e.as_fail()
-            ))),
+    /// Parse the schemas of several `.avsc` files, where each file may also
+    /// refer to named types defined in the previous ones.
+    #[staticmethod]
+    #[args(search_paths = "Vec::new()")]
+    fn from_files(
+        py: Python,
+        paths: Vec<FsPath>,
+        search_paths: Vec<FsPath>,
+    ) -> PyResult<Vec<Schema>> {
+        let paths = paths.into_iter().map(|path| path.0).collect::<Vec<_>>();
+        let search_paths = search_paths
+            .into_iter()
+            .map(|path| path.0)
+            .collect::<Vec<_>>();
+        let start = Instant::now();
+        let parsed = resolver::parse_files(&paths, &search_paths);
+        log::log(py, log::DEBUG, || match &parsed {
+            Ok(_) => format!(
+                "Parsed {} in {:?}",
+                paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                start.elapsed()
+            ),
+            Err(e) => format!("Failed to parse schema files: {}", e),
+        });
+        match parsed {
+            Ok(schemas) => Ok(schemas
+                .into_iter()
+                .map(|(schema, json)| Schema::build(py, Config::default(), schema, json))
+                .collect()),
+            Err(e) => Err(e.into_pyerr(py)),
+        }
+    }
+
+    /// Derive a record schema from a pydantic model class, naming fields by
+    /// their alias. The model and its nested models are registered, so
+    /// records decode to model instances.
+    #[staticmethod]
+    fn from_pydantic(py: Python, model: &PyType) -> PyResult<Schema> {
+        if !pydantic::is_model(model) {
+            return Err(PyErr::new::<exceptions::TypeError, _>(
+                "Expected a pydantic model class",
+            ));
+        }
+        schema_from_type(py, model)
+    }
+
+    /// The JSON of the schema, custom attributes included, with named types
+    /// defined once and qualified with their namespace. Raises a `ValueError`
+    /// if a full name has several different definitions.
+    fn to_json(&self) -> PyResult<String> {
+        match resolver::with_references(&self.json) {
+            Ok(json) => Ok(json.to_string()),
+            Err(e) => Err(PyErr::new::<exceptions::ValueError, _>(e)),
+        }
+    }
+
+    /// Custom (non-standard) attributes of the schema, or of the record field
+    /// at the dotted `field` path (e.g. `"user.email"`).
+    #[args(field = "None")]
+    fn attributes(&self, py: Python, field: Option<&str>) -> PyResult<PyObject> {
+        let attributes = match field {
+            None => self
+                .json
+                .as_object()
+                .map(attributes::custom)
+                .unwrap_or_default(),
+            Some(path) => {
+                let segments = path.split('.').collect::<Vec<_>>();
+                match attributes::field(&self.json, &segments) {
+                    Some(definition) => attributes::custom(definition),
+                    None => {
+                        return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                            "Unknown field path: {}",
+                            path
+                        )))
+                    }
+                }
+            }
+        };
+        let json = JsonValue::Object(attributes).to_string();
+        Ok(py.import("json")?.call1("loads", (json,))?.to_object(py))
+    }
+
+    /// Derive the schema keeping only the record fields at the dotted
+    /// `paths`: `"b"` keeps field `b` whole, `"b.c"` only field `c` of `b`.
+    /// Names and namespaces are kept, so the projection stays resolvable
+    /// against the original schema; a named type used at several paths must
+    /// have the same fields selected at each.
+    fn project(&self, py: Python, paths: Vec<&str>) -> PyResult<Schema> {
+        let mut selection = project::Selection::default();
+        for path in paths {
+            let segments = path.split('.').collect::<Vec<_>>();
+            if attributes::field(&self.json, &segments).is_none() {
+                return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                    "Unknown field path: {}",
+                    path
+                )));
+            }
+            selection.insert(&segments);
+        }
+        match project::project(&self.json, &selection) {
+            Ok(json) => Schema::derived(py, json),
+            Err(e) => Err(PyErr::new::<exceptions::ValueError, _>(e)),
+        }
+    }
+
+    /// Derive the schema with a field added to the top-level record, or to
+    /// the nested record at the dotted `path` of its parent. `field_type` is
+    /// the JSON of the field schema. A `default` is required, so data
+    /// written with the current schema stays readable; `doc` is optional.
+    #[args(kwargs = "**")]
+    fn with_field(
+        &self,
+        py: Python,
+        path: &str,
+        field_type: &str,
+        kwargs: Option<&PyDict>,
+    ) -> PyResult<Schema> {
+        let segments = path.split('.').collect::<Vec<_>>();
+        let (name, parent) = segments.split_last().unwrap_or((&"", &[]));
+        let mut field = serde_json::Map::new();
+        field.insert("name".to_string(), JsonValue::String(name.to_string()));
+        let field_type = serde_json::from_str(field_type)
+            .map_err(|e| errors::ParseError::from(e).into_pyerr(py))?;
+        field.insert("type".to_string(), field_type);
+        for (key, value) in kwargs.into_iter().flat_map(PyDict::iter) {
+            let key = key.extract::<String>()?;
+            if key != "default" && key != "doc" {
+                return Err(PyErr::new::<exceptions::TypeError, _>(format!(
+                    "Unexpected argument: {}",
+                    key
+                )));
+            }
+            let value = py.import("json")?.call1("dumps", (value,))?;
+            let value = serde_json::from_str(value.extract()?)
+                .map_err(|e| PyErr::new::<exceptions::ValueError, _>(e.to_string()))?;
+            field.insert(key, value);
+        }
+        if !field.contains_key("default") {
+            return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                "Field {} needs a default",
+                path
+            )));
+        }
+
+        validate::check_default(&field, path).map_err(|e| e.into_pyerr(py))?;
+        let mut json = self.json.clone();
+        compose::add_field(&mut json, parent, field)
+            .map_err(PyErr::new::<exceptions::ValueError, _>)?;
+        Schema::derived(py, json)
+    }
+
+    /// Derive the schema without the field at the dotted `path`.
+    fn without_field(&self, py: Python, path: &str) -> PyResult<Schema> {
+        let mut json = self.json.clone();
+        compose::remove_field(&mut json, &path.split('.').collect::<Vec<_>>())
+            .map_err(PyErr::new::<exceptions::ValueError, _>)?;
+        Schema::derived(py, json)
+    }
+
+    /// Derive the schema whose top-level record has the fields of both
+    /// schemas. Fields present in both must be defined identically, and
+    /// fields added from `other` need a default.
+    fn merge(&self, py: Python, other: &Schema) -> PyResult<Schema> {
+        let mut json = self.json.clone();
+        compose::merge(&mut json, &other.json).map_err(|e| e.into_pyerr(py))?;
+        Schema::derived(py, json)
+    }
+
+    /// CRC-64-AVRO fingerprint of the schema Parsing Canonical Form,
+    /// little-endian as found in single object encoded messages.
+    #[getter]
+    fn fingerprint<'p>(&self, py: Python<'p>) -> &'p PyBytes {
+        PyBytes::new(py, &self.fingerprint_bytes())
+    }
+
+    /// Register Python classes for record and enum types, keyed by full name.
+    ///
+    /// Instances of a registered record class are encoded from their
+    /// attributes (selecting the matching union branch), and decoded records
+    /// are instantiated by calling the class with the fields as keyword
+    /// arguments. Decoded enum symbols are looked up on the registered class.
+    ///
+    /// A registered class selects the single record branch of a union:
+    /// avro-rs 0.10 unions can hold only one record, so a registered class
+    /// cannot choose among several records.
+    fn register_types(&mut self, py: Python, types: &PyDict) -> PyResult<()> {
+        let mut names = Vec::new();
+        named_types(&self.schema, &mut names);
+
+        for (name, cls) in types.iter() {
+            let name = name.extract::<String>()?;
+            cls.cast_as::<PyType>()?;
+            if !names.contains(&name) {
+                return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                    "Unknown record or enum type: {}",
+                    name
+                )));
+            }
+            self.config.types.insert(name, cls.to_object(py));
+        }
+        Ok(())
+    }
+
+    /// Register callables transforming field values, keyed by dotted field
+    /// path (e.g. `"user.email"`): `encode` hooks receive the Python value
+    /// before it is encoded, `decode` hooks the decoded Python value.
+    #[args(encode = "None", decode = "None")]
+    fn register_hooks(
+        &mut self,
+        py: Python,
+        encode: Option<&PyDict>,
+        decode: Option<&PyDict>,
+    ) -> PyResult<()> {
+        for (hooks, registry) in [
+            (encode, &mut self.config.encode_hooks),
+            (decode, &mut self.config.decode_hooks),
+        ] {
+            for (path, hook) in hooks.into_iter().flat_map(PyDict::iter) {
+                let path = path.extract::<&str>()?;
+                let segments = path.split('.').collect::<Vec<_>>();
+                if !hooks::field_exists(&self.schema, &segments) {
+                    return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                        "Unknown field path: {}",
+                        path
+                    )));
+                }
+                registry.insert(path, hook.to_object(py));
+            }
+        }
+        Ok(())
+    }
+
+    /// Register conversions of decoded field values for legacy schemas,
+    /// mapping a coercion to the dotted paths of the fields it applies to,
+    /// e.g. `{"string->int": ["user_id"], "long->datetime(ms)": ["created"]}`.
+    ///
+    /// Coercions are `string->int`, `string->float` and
+    /// `int->datetime(unit)` or `long->datetime(unit)` with `unit` one of
+    /// `s`, `ms` or `us`. They do not apply to values of other types, such
+    /// as `null`, and run before decode hooks.
+    fn register_coercions(&mut self, coercions: &PyDict) -> PyResult<()> {
+        for (name, paths) in coercions.iter() {
+            let coercion = name.extract::<&str>()?.parse::<coerce::Coercion>()?;
+            for path in paths.extract::<Vec<&str>>()? {
+                let segments = path.split('.').collect::<Vec<_>>();
+                if !hooks::field_exists(&self.schema, &segments) {
+                    return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                        "Unknown field path: {}",
+                        path
+                    )));
+                }
+                self.config.coercions.insert(path, coercion);
+            }
         }
+        Ok(())
+    }
+
+    /// Register a callable receiving every datum passed to `write` and
+    /// returning the datum to encode, e.g. to trim or lower-case values in
+    /// one place. Field hooks apply to the datum it returns. `None`
+    /// unregisters it.
+    fn register_normalizer(&mut self, normalizer: Option<PyObject>) {
+        self.config.normalizer = normalizer;
+    }
+
+    /// Encode `datum`. For union schemas, `branch` selects the variant to
+    /// encode with instead of picking the first one accepting the datum.
+    ///
+    /// In unions with both an `int` and a `long` branch, Python ints are
+    /// encoded as `int` when they fit in 32 bits and as `long` otherwise,
+    /// whatever the order of the branches.
+    #[args(branch = "None")]
+    fn write<'p>(
+        &self,
+        py: Python<'p>,
+        datum: PyObject,
+        branch: Option<&str>,
+    ) -> PyResult<&'p PyBytes> {
+        Ok(PyBytes::new(py, &self.encode(py, datum, branch)?))
+    }
+
+    /// Encode `datum` like `write`, into a read-only buffer object exposing
+    /// the encoding through the buffer protocol without copying it into
+    /// `bytes`, e.g. for `memoryview` or `socket.send`.
+    #[args(branch = "None")]
+    fn write_buffer(
+        &self,
+        py: Python,
+        datum: PyObject,
+        branch: Option<&str>,
+    ) -> PyResult<EncodedBuffer> {
+        Ok(EncodedBuffer::new(self.encode(py, datum, branch)?))
     }
 
     fn read(&self, py: Python, datum: &PyBytes) -> PyResult<PyObject> {
-        let mut bytes = datum.as_bytes();
-        match from_avro_datum(&self.schema, &mut bytes, None) {
-            Ok(value) => to_pyobject(py, value),
-            Err(e) => Err(PyErr::new::<exceptions::ValueError, _>(format!(
-                "{}",
-                e.as_fail()
-            ))),
+        let value = self.decode_with(datum.py(), datum.as_bytes(), self.reader_schema(), None)?;
+        to_pyobject(py, value, &self.schema, &self.config, None)
+    }
+
+    /// Decode several datums written back to back in `buffer`. With `prefix`
+    /// `"count"`, the datums follow their number as an Avro `long`; with
+    /// `"length"`, each datum follows its size in bytes as an Avro `long`.
+    /// Otherwise datums are decoded until the end of the buffer.
+    #[args(prefix = "None")]
+    fn read_stream(
+        &self,
+        py: Python,
+        buffer: &PyBytes,
+        prefix: Option<&str>,
+    ) -> PyResult<Vec<PyObject>> {
+        let bytes = buffer.as_bytes();
+        let mut cursor = Cursor::new(bytes, prefix)?;
+        let mut datums = Vec::with_capacity(cursor.remaining(bytes));
+        while let Some(value) = cursor.next(self.reader_schema(), bytes)? {
+            datums.push(to_pyobject(py, value, &self.schema, &self.config, None)?);
+        }
+        Ok(datums)
+    }
+
+    /// Lazy counterpart of `read_stream`, returning an iterator that decodes
+    /// one datum per step, so that callers can stop early.
+    #[args(prefix = "None")]
+    fn read_iter(
+        slf: PyRef<Self>,
+        py: Python,
+        buffer: Py<PyBytes>,
+        prefix: Option<&str>,
+    ) -> PyResult<DatumIter> {
+        DatumIter::new(py, slf.into(), buffer, prefix)
+    }
+
+    /// Estimate the bytes of Python heap that decoding `datum` would take,
+    /// without building the Python objects. Record field names are shared
+    /// between records and not counted.
+    fn estimate_decoded_size(&self, datum: &PyBytes) -> PyResult<usize> {
+        Ok(size::estimate(&self.decode(datum)?))
+    }
+
+    /// Number of bytes `write` would produce for `datum`, computed without
+    /// encoding it, e.g. to pack records into size-bounded batches.
+    fn encoded_size(&self, py: Python, datum: PyObject) -> PyResult<usize> {
+        let datum = match &self.config.normalizer {
+            Some(normalizer) => normalizer.call1(py, (datum,))?,
+            None => datum,
+        };
+        let value = to_avro_value(py, &datum, &self.schema, &self.config, None)?;
+        if !value.validate(&self.schema) {
+            return Err(PyErr::new::<exceptions::ValueError, _>(
+                "value does not match schema",
+            ));
+        }
+        Ok(size::encoded(&value, &self.schema))
+    }
+
+    /// Decode `datum` straight to MessagePack bytes, without building
+    /// Python objects. Registered types and hooks do not apply.
+    fn read_to_msgpack<'p>(&self, py: Python<'p>, datum: &PyBytes) -> PyResult<&'p PyBytes> {
+        let mut out = Vec::new();
+        msgpack::write(&self.decode(datum)?, self.config.sort_maps, &mut out);
+        Ok(PyBytes::new(py, &out))
+    }
+
+    /// Decode a datum of a union schema as a `(branch, value)` tuple, where
+    /// `branch` is the full name of named types and the type name otherwise.
+    fn read_union(&self, py: Python, datum: &PyBytes) -> PyResult<(String, PyObject)> {
+        match (
+            &self.schema,
+            self.decode_with(datum.py(), datum.as_bytes(), self.reader_schema(), None)?,
+        ) {
+            (SchemaRs::Union(inner), Value::Union(value)) => match union_variant(inner, &value) {
+                Some(variant) => Ok((
+                    branch_name(variant),
+                    to_pyobject(py, *value, variant, &self.config, None)?,
+                )),
+                None => Err(PyErr::new::<exceptions::ValueError, _>(
+                    "Decoded value matches no union branch",
+                )),
+            },
+            _ => Err(PyErr::new::<exceptions::ValueError, _>(
+                "Schema is not a union",
+            )),
         }
     }
 }
 
-fn to_pyobject(py: Python, datum: Value) -> PyResult<PyObject> {
-    match datum {
-        Value::Null => Ok(py.None()),
-        Value::Boolean(b) => Ok(b.to_object(py)),
-        Value::Int(n) => Ok(n.to_object(py)),
-        Value::Long(n) => Ok(n.to_object(py)),
-        Value::Float(x) => Ok(x.to_object(py)),
-        Value::Double(x) => Ok(x.to_object(py)),
-        Value::Bytes(bytes) => Ok(bytes.to_object(py)),
-        Value::String(string) => Ok(string.to_object(py)),
-        Value::Fixed(_, bytes) => Ok(bytes.to_object(py)),
-        Value::Enum(_, symbol) => Ok(symbol.to_object(py)),
-        Value::Union(item) => to_pyobject(py, *item),
-        Value::Array(items) => {
+impl Schema {
+    fn build(py: Python, mut config: Config, schema: SchemaRs, json: JsonValue) -> Self {
+        field_keys(py, &schema, &mut config.keys);
+        enum_symbols(&schema, &mut config.symbols);
+        let lenient = match config.decode_errors {
+            Some(_) => SchemaRs::parse(&strings::as_bytes(&json)).ok(),
+            None => None,
+        };
+        Schema {
+            schema,
+            json,
+            lenient,
+            config,
+        }
+    }
+
+    /// A schema parsed from JSON derived from this one, with default settings.
+    fn derived(py: Python, json: JsonValue) -> PyResult<Schema> {
+        match validate::parse_json(&json) {
+            Ok((schema, json)) => Ok(Schema::build(py, Config::default(), schema, json)),
+            Err(e) => Err(e.into_pyerr(py)),
+        }
+    }
+
+    fn decode(&self, datum: &PyBytes) -> PyResult<Value> {
+        self.decode_with(datum.py(), datum.as_bytes(), &self.schema, None)
+    }
+
+    /// Little-endian CRC-64-AVRO fingerprint of the Parsing Canonical Form.
+    pub(crate) fn fingerprint_bytes(&self) -> [u8; 8] {
+        rabin::fingerprint(canonical::canonical_form(&self.json).as_bytes()).to_le_bytes()
+    }
+
+    /// The schema to decode data written with `writer` before resolving it
+    /// against `reader_schema()`, reading invalid UTF-8 strings as bytes
+    /// when `decode_errors` is set.
+    pub(crate) fn writer_schema<'a>(&self, writer: &'a Schema) -> Cow<'a, SchemaRs> {
+        match (&self.lenient, &writer.lenient) {
+            (Some(_), Some(lenient)) => Cow::Borrowed(lenient),
+            (Some(_), None) => SchemaRs::parse(&strings::as_bytes(&writer.json))
+                .map_or(Cow::Borrowed(&writer.schema), Cow::Owned),
+            (None, _) => Cow::Borrowed(&writer.schema),
+        }
+    }
+
+    /// The schema `read` decodes with, accepting invalid UTF-8 strings when
+    /// `decode_errors` is set.
+    pub(crate) fn reader_schema(&self) -> &SchemaRs {
+        self.lenient.as_ref().unwrap_or(&self.schema)
+    }
+
+    fn encode(&self, py: Python, datum: PyObject, branch: Option<&str>) -> PyResult<Vec<u8>> {
+        let datum = match &self.config.normalizer {
+            Some(normalizer) => normalizer.call1(py, (datum,))?,
+            None => datum,
+        };
+        let value = match branch {
+            Some(branch) => to_avro_branch(py, &datum, &self.schema, &self.config, branch)?,
+            None => to_avro_value(py, &datum, &self.schema, &self.config, None)?,
+        };
+
+        match to_avro_datum(&self.schema, value) {
+            Ok(bytes) => Ok(bytes),
+            Err(e) => {
+                log::log(py, log::DEBUG, || {
+                    format!("Failed to encode datum: {:?}", e.as_fail())
+                });
+                Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                    "{}",
+                    e.as_fail()
+                )))
+            }
+        }
+    }
+
+    /// Decode `datum` written with `schema`, resolved against `reader` if
+    /// given, logging failures.
+    pub(crate) fn decode_with(
+        &self,
+        py: Python,
+        datum: &[u8],
+        schema: &SchemaRs,
+        reader: Option<&SchemaRs>,
+    ) -> PyResult<Value> {
+        let mut bytes = datum;
+        match from_avro_datum(schema, &mut bytes, reader) {
+            Ok(value) => Ok(value),
+            Err(e) => {
+                log::log(py, log::DEBUG, || {
+                    format!("Failed to decode {} bytes: {:?}", datum.len(), e.as_fail())
+                });
+                Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                    "{}",
+                    e.as_fail()
+                )))
+            }
+        }
+    }
+}
+
+/// Name identifying a union branch: the full name of named types, and the
+/// (logical) type name otherwise.
+fn branch_name(schema: &SchemaRs) -> String {
+    match schema {
+        SchemaRs::Null => "null".to_string(),
+        SchemaRs::Boolean => "boolean".to_string(),
+        SchemaRs::Int => "int".to_string(),
+        SchemaRs::Long => "long".to_string(),
+        SchemaRs::Float => "float".to_string(),
+        SchemaRs::Double => "double".to_string(),
+        SchemaRs::Bytes => "bytes".to_string(),
+        SchemaRs::String => "string".to_string(),
+        SchemaRs::Array(_) => "array".to_string(),
+        SchemaRs::Map(_) => "map".to_string(),
+        SchemaRs::Union(_) => "union".to_string(),
+        SchemaRs::Record { name, .. }
+        | SchemaRs::Enum { name, .. }
+        | SchemaRs::Fixed { name, .. } => name.fullname(None),
+        SchemaRs::Decimal { .. } => "decimal".to_string(),
+        SchemaRs::Uuid => "uuid".to_string(),
+        SchemaRs::Date => "date".to_string(),
+        SchemaRs::TimeMillis => "time-millis".to_string(),
+        SchemaRs::TimeMicros => "time-micros".to_string(),
+        SchemaRs::TimestampMillis => "timestamp-millis".to_string(),
+        SchemaRs::TimestampMicros => "timestamp-micros".to_string(),
+        SchemaRs::Duration => "duration".to_string(),
+    }
+}
+
+/// The variant of the union `inner` matching `value`. `string` variants
+/// match `bytes` values, as read when `decode_errors` is set.
+fn union_variant<'a>(inner: &'a UnionSchema, value: &Value) -> Option<&'a SchemaRs> {
+    match (inner.find_schema(value), value) {
+        (Some((_, variant)), _) => Some(variant),
+        (None, Value::Bytes(_)) => inner
+            .variants()
+            .iter()
+            .find(|variant| **variant == SchemaRs::String),
+        (None, _) => None,
+    }
+}
+
+/// Collect the full names of all record types defined in `schema`.
+fn named_types(schema: &SchemaRs, names: &mut Vec<String>) {
+    match schema {
+        SchemaRs::Array(inner) | SchemaRs::Map(inner) => named_types(inner, names),
+        SchemaRs::Union(inner) => {
+            for variant in inner.variants() {
+                named_types(variant, names);
+            }
+        }
+        SchemaRs::Record { name, fields, .. } => {
+            names.push(name.fullname(None));
+            for field in fields {
+                named_types(&field.schema, names);
+            }
+        }
+        SchemaRs::Enum { name, .. } => names.push(name.fullname(None)),
+        _ => {}
+    }
+}
+
+/// Build the Python strings of the field names of the records of `schema`.
+/// Records whose full name has definitions with different fields, as
+/// projection or composition can produce, are left out and have their
+/// fields looked up by name.
+fn field_keys(py: Python, schema: &SchemaRs, keys: &mut HashMap<String, Vec<PyObject>>) {
+    let mut definitions = HashMap::new();
+    record_fields(schema, &mut definitions);
+    for (name, fields) in definitions {
+        if let Some(fields) = fields {
+            let names = fields
+                .iter()
+                .map(|field| PyString::new(py, &field.name).to_object(py))
+                .collect();
+            keys.insert(name, names);
+        }
+    }
+}
+
+/// Collect the fields of the records of `schema` by full name, or `None`
+/// for names defined with different field names.
+fn record_fields<'a>(
+    schema: &'a SchemaRs,
+    definitions: &mut HashMap<String, Option<&'a [RecordField]>>,
+) {
+    match schema {
+        SchemaRs::Array(inner) | SchemaRs::Map(inner) => record_fields(inner, definitions),
+        SchemaRs::Union(inner) => {
+            for variant in inner.variants() {
+                record_fields(variant, definitions);
+            }
+        }
+        SchemaRs::Record { name, fields, .. } => {
+            let known = definitions
+                .entry(name.fullname(None))
+                .or_insert(Some(fields));
+            let same = known.is_some_and(|known| {
+                known
+                    .iter()
+                    .map(|field| &field.name)
+                    .eq(fields.iter().map(|field| &field.name))
+            });
+            if !same {
+                *known = None;
+            }
+            for field in fields {
+                record_fields(&field.schema, definitions);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Index the symbols of the enums of `schema`.
+fn enum_symbols(schema: &SchemaRs, indices: &mut HashMap<String, HashMap<String, i32>>) {
+    match schema {
+        SchemaRs::Array(inner) | SchemaRs::Map(inner) => enum_symbols(inner, indices),
+        SchemaRs::Union(inner) => {
+            for variant in inner.variants() {
+                enum_symbols(variant, indices);
+            }
+        }
+        SchemaRs::Record { fields, .. } => {
+            for field in fields {
+                enum_symbols(&field.schema, indices);
+            }
+        }
+        SchemaRs::Enum { name, symbols, .. } => {
+            let index = symbols
+                .iter()
+                .enumerate()
+                .map(|(i, symbol)| (symbol.clone(), i as i32))
+                .collect();
+            indices.insert(name.fullname(None), index);
+        }
+        _ => {}
+    }
+}
+
+/// Convert `datum` using the variant of the union `schema` named `branch`.
+fn to_avro_branch(
+    py: Python,
+    datum: &PyObject,
+    schema: &SchemaRs,
+    config: &Config,
+    branch: &str,
+) -> PyResult<Value> {
+    let variants = match schema {
+        SchemaRs::Union(inner) => inner.variants(),
+        _ => {
+            return Err(PyErr::new::<exceptions::ValueError, _>(
+                "Schema is not a union",
+            ))
+        }
+    };
+    match variants
+        .iter()
+        .find(|variant| branch_name(variant) == branch)
+    {
+        Some(variant) => Ok(Value::Union(Box::new(to_avro_value(
+            py, datum, variant, config, None,
+        )?))),
+        None => Err(PyErr::new::<exceptions::ValueError, _>(format!(
+            "Unknown union branch: {}",
+            branch
+        ))),
+    }
+}
+
+fn to_pyobject(
+    py: Python,
+    datum: Value,
+    schema: &SchemaRs,
+    config: &Config,
+    path: Option<&FieldPath>,
+) -> PyResult<PyObject> {
+    match (datum, schema) {
+        (Value::Null, _) => Ok(py.None()),
+        (Value::Boolean(b), _) => Ok(b.to_object(py)),
+        (Value::Int(n), _) => Ok(n.to_object(py)),
+        (Value::Long(n), _) => Ok(n.to_object(py)),
+        (Value::Float(x), _) => Ok(x.to_object(py)),
+        (Value::Double(x), _) => Ok(x.to_object(py)),
+        // Strings read as bytes, see `decode_errors`.
+        (Value::Bytes(bytes), SchemaRs::String) => match std::str::from_utf8(&bytes) {
+            Ok(string) => Ok(string.to_object(py)),
+            Err(_) => match config.decode_errors.as_deref() {
+                Some("bytes") => Ok(PyBytes::new(py, &bytes).to_object(py)),
+                errors => Ok(PyBytes::new(py, &bytes)
+                    .call_method1("decode", ("utf-8", errors.unwrap_or("strict")))?
+                    .to_object(py)),
+            },
+        },
+        (Value::Bytes(bytes), _) => match &config.decode_bytes {
+            Some(encoding) => Ok(PyBytes::new(py, &bytes)
+                .call_method1("decode", (encoding.as_str(),))?
+                .to_object(py)),
+            None => Ok(PyBytes::new(py, &bytes).to_object(py)),
+        },
+        (Value::String(string), _) => Ok(string.to_object(py)),
+        (Value::Fixed(_, bytes), _) => Ok(PyBytes::new(py, &bytes).to_object(py)),
+        (Value::Enum(_, symbol), SchemaRs::Enum { name, .. }) => {
+            match config.types.get(&name.fullname(None)) {
+                Some(cls) => cls.call_method1(py, "__getitem__", (symbol,)),
+                None => Ok(symbol.to_object(py)),
+            }
+        }
+        (Value::Enum(_, symbol), _) => Ok(symbol.to_object(py)),
+        (Value::Date(days), _) => logical::to_date(py, days),
+        (Value::TimestampMillis(millis), _) => match millis.checked_mul(1_000) {
+            Some(micros) => logical::to_datetime(py, micros),
+            None => Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                "timestamp-millis {} is out of range",
+                millis
+            ))),
+        },
+        (Value::TimestampMicros(micros), _) => logical::to_datetime(py, micros),
+        (Value::TimeMillis(millis), _) => logical::to_time(py, i64::from(millis) * 1_000),
+        (Value::TimeMicros(micros), _) => logical::to_time(py, micros),
+        (Value::Uuid(uuid), _) => logical::to_uuid(py, uuid.to_string()),
+        (Value::Decimal(decimal), SchemaRs::Decimal { scale, .. }) => {
+            match Vec::try_from(&decimal) {
+                Ok(unscaled) => logical::to_decimal(py, &unscaled, *scale),
+                Err(_) => unsupported(py, Value::Decimal(decimal), schema, config),
+            }
+        }
+        (Value::Union(item), SchemaRs::Union(inner)) => {
+            let variant = union_variant(inner, &item).unwrap_or(schema);
+            let value = to_pyobject(py, *item, variant, config, path)?;
+            if config.union_branches {
+                Ok((branch_name(variant), value).to_object(py))
+            } else {
+                Ok(value)
+            }
+        }
+        (Value::Array(items), SchemaRs::Array(inner)) => {
+            if config.arrays != ArrayMode::List {
+                if let Some(array) = buffer::to_array(py, &items, inner, config.arrays)? {
+                    return Ok(array);
+                }
+            }
             // TODO
             let list = PyList::empty(py);
             for item in items {
-                list.append(to_pyobject(py, item)?)?;
+                list.append(to_pyobject(py, item, inner, config, path)?)?;
             }
             Ok(list.to_object(py))
         }
-        Value::Map(items) => {
-            // TODO
+        (Value::Map(items), SchemaRs::Map(inner)) => {
+            let mut items = items.into_iter().collect::<Vec<_>>();
+            if config.sort_maps {
+                items.sort_by(|(a, _), (b, _)| a.cmp(b));
+            }
             let dict = PyDict::new(py);
             for (key, value) in items {
-                dict.set_item(key, to_pyobject(py, value)?)?;
+                dict.set_item(key, to_pyobject(py, value, inner, config, path)?)?;
             }
             Ok(dict.to_object(py))
         }
-        Value::Record(fields) => {
+        (
+            Value::Record(fields),
+            SchemaRs::Record {
+                name,
+                fields: rfields,
+                ..
+            },
+        ) => {
+            let fullname = name.fullname(None);
+            let keys = config.keys.get(&fullname);
             let dict = PyDict::new(py);
-            for (name, value) in fields {
-                dict.set_item(name, to_pyobject(py, value)?)?;
+            for (i, ((name, value), field)) in fields.into_iter().zip(rfields).enumerate() {
+                let path = FieldPath::child(path, &field.name);
+                let coerced = config
+                    .coercions
+                    .get(&path)
+                    .and_then(|coercion| coercion.apply(py, &value, &field.name));
+                let mut value = match coerced {
+                    Some(coerced) => coerced?,
+                    None => to_pyobject(py, value, &field.schema, config, Some(&path))?,
+                };
+                if let Some(hook) = config.decode_hooks.get(&path) {
+                    value = hook.call1(py, (value,))?;
+                }
+                match keys.and_then(|keys| keys.get(i)) {
+                    Some(key) => dict.set_item(key, value)?,
+                    None => dict.set_item(name, value)?,
+                }
+            }
+            match config.types.get(&fullname) {
+                Some(cls) => cls.call(py, (), Some(dict)),
+                None => Ok(dict.to_object(py)),
             }
-            Ok(dict.to_object(py))
         }
-        _ => Ok(py.None()),
+        // Durations, and values not matching their schema.
+        (datum @ Value::Duration(_), _)
+        | (datum @ Value::Decimal(_), _)
+        | (datum @ Value::Union(_), _)
+        | (datum @ Value::Array(_), _)
+        | (datum @ Value::Map(_), _)
+        | (datum @ Value::Record(_), _) => unsupported(py, datum, schema, config),
     }
 }
 
-fn to_avro_value(py: Python, datum: &PyObject, schema: &SchemaRs) -> PyResult<Value> {
+/// Apply the `unsupported` policy of `config` to a decoded `datum`.
+fn unsupported(py: Python, datum: Value, schema: &SchemaRs, config: &Config) -> PyResult<PyObject> {
+    let kind = match &datum {
+        Value::Duration(_) => "duration".to_string(),
+        _ => branch_name(schema),
+    };
+    match config.unsupported {
+        Unsupported::Raise => Err(PyErr::new::<exceptions::TypeError, _>(format!(
+            "Unsupported {} value",
+            kind
+        ))),
+        Unsupported::Warn => {
+            let category = py.import("builtins")?.getattr("RuntimeWarning")?;
+            let message = format!("Unsupported {} value decoded as None", kind);
+            PyErr::warn(py, category, &message, 1)?;
+            Ok(py.None())
+        }
+        Unsupported::Datum => {
+            let raw = to_avro_datum(schema, datum).ok();
+            Ok(PyCell::new(py, Datum::new(py, kind, raw))?.to_object(py))
+        }
+    }
+}
+
+fn to_avro_value(
+    py: Python,
+    datum: &PyObject,
+    schema: &SchemaRs,
+    config: &Config,
+    path: Option<&FieldPath>,
+) -> PyResult<Value> {
     match schema {
-        &SchemaRs::Null if datum.is_none(py) => Ok(Value::Null),
-        &SchemaRs::Null => Err(PyErr::from(PyDowncastError)),
-        &SchemaRs::Boolean => {
+        SchemaRs::Null if datum.is_none(py) => Ok(Value::Null),
+        SchemaRs::Null => Err(PyErr::from(PyDowncastError)),
+        SchemaRs::Boolean => {
             let b = datum.extract::<bool>(py)?;
             Ok(Value::Boolean(b))
         }
-        &SchemaRs::Int => {
+        SchemaRs::Int => {
             // TODO: PyInt/PyLong?
             let n = datum.extract::<i32>(py)?;
             Ok(Value::Int(n))
         }
-        &SchemaRs::Long => {
+        SchemaRs::Long => {
             // TODO: PyInt/PyLong?
             let n = datum.extract::<i64>(py)?;
             Ok(Value::Long(n))
         }
-        &SchemaRs::Float => {
+        SchemaRs::Float => {
             let x = datum.extract::<f32>(py)?;
             Ok(Value::Float(x))
         }
-        &SchemaRs::Double => {
+        SchemaRs::Double => {
             let x = datum.extract::<f64>(py)?;
             Ok(Value::Double(x))
         }
-        &SchemaRs::Bytes => {
+        SchemaRs::Bytes => {
+            if let Ok(bytes) = datum.cast_as::<PyBytes>(py) {
+                return Ok(Value::Bytes(bytes.as_bytes().to_vec()));
+            }
+            if let Ok(string) = datum.cast_as::<PyString>(py) {
+                return match &config.bytes_encoding {
+                    Some(encoding) if encoding == "utf-8" => {
+                        Ok(Value::Bytes(string.to_string()?.as_bytes().to_vec()))
+                    }
+                    Some(encoding) => {
+                        let bytes = string.call_method1("encode", (encoding.as_str(),))?;
+                        Ok(Value::Bytes(
+                            bytes.cast_as::<PyBytes>()?.as_bytes().to_vec(),
+                        ))
+                    }
+                    None => Err(PyErr::new::<exceptions::TypeError, _>(
+                        "str is not accepted for bytes without bytes_encoding",
+                    )),
+                };
+            }
             let bytes = datum.extract::<Vec<u8>>(py)?;
             Ok(Value::Bytes(bytes))
         }
-        &SchemaRs::String => {
-            let string = datum.extract::<String>(py)?;
-            Ok(Value::String(string))
-        }
-        &SchemaRs::Array(ref inner) => {
+        SchemaRs::String => match (datum.extract::<String>(py), &config.encode_errors) {
+            (Ok(string), _) => Ok(Value::String(string)),
+            (Err(e), Some(errors)) if datum.cast_as::<PyString>(py).is_ok() => {
+                let bytes = datum.call_method1(py, "encode", ("utf-8", errors.as_str()))?;
+                match String::from_utf8(bytes.cast_as::<PyBytes>(py)?.as_bytes().to_vec()) {
+                    Ok(string) => Ok(Value::String(string)),
+                    Err(_) => Err(e),
+                }
+            }
+            (Err(e), _) => Err(e),
+        },
+        SchemaRs::Array(inner) => {
+            if let Some(items) = buffer::from_buffer(py, datum, inner)? {
+                return Ok(Value::Array(items));
+            }
             // TODO: PyTuple?
             let array = datum.extract::<Vec<PyObject>>(py)?;
             let items = array
                 .iter()
-                .map(|item| to_avro_value(py, &item, inner))
+                .map(|item| to_avro_value(py, item, inner, config, path))
                 .collect::<PyResult<Vec<Value>>>()?;
             Ok(Value::Array(items))
         }
-        &SchemaRs::Map(ref inner) => {
+        SchemaRs::Map(inner) => {
             let items = datum
                 .cast_as::<PyDict>(py)?
                 .iter()
                 .map(|(keyo, valueo)| {
                     Ok((
                         keyo.extract::<String>()?,
-                        to_avro_value(py, &valueo.to_object(py), inner)?,
+                        to_avro_value(py, &valueo.to_object(py), inner, config, path)?,
                     ))
                 })
                 .collect::<PyResult<HashMap<String, Value>>>()?;
 
             Ok(Value::Map(items))
         }
-        &SchemaRs::Union(ref inner) => {
+        SchemaRs::Union(inner) => {
             // Optimization for when union is used for optional values
-            if inner.is_nullable() & &datum.is_none(py) {
+            if inner.is_nullable() && datum.is_none(py) {
                 Ok(Value::Union(Box::new(Value::Null)))
             } else {
                 let variants = inner.variants();
+                if let Some((branch, datum)) = branch_tuple(py, datum, config) {
+                    if let Some(variant) = variants.iter().find(|v| branch_name(v) == branch) {
+                        let value = to_avro_value(py, &datum, variant, config, path)?;
+                        return Ok(Value::Union(Box::new(value)));
+                    }
+                }
+                // Instances of registered classes pick their record branch directly
                 for variant in variants {
-                    let value = to_avro_value(py, datum, variant);
-                    match value {
+                    if is_registered_instance(py, datum, variant, config)? {
+                        let value = to_avro_value(py, datum, variant, config, path)?;
+                        return Ok(Value::Union(Box::new(value)));
+                    }
+                }
+                if let Some(value) = to_avro_integer(py, datum, variants) {
+                    return Ok(Value::Union(Box::new(value)));
+                }
+                // Type mismatches are expected while looking for the branch,
+                // other errors are chained as they are likely bugs of the datum,
+                // including those chained by the failure of a nested union.
+                let mut cause = None;
+                // A str only goes to a `bytes` branch (through `bytes_encoding`)
+                // when there is no `string` branch to keep it a string.
+                let skip_bytes =
+                    datum.cast_as::<PyString>(py).is_ok() && variants.contains(&SchemaRs::String);
+                for variant in variants {
+                    if skip_bytes && *variant == SchemaRs::Bytes {
+                        continue;
+                    }
+                    match to_avro_value(py, datum, variant, config, path) {
                         Ok(v) => return Ok(Value::Union(Box::new(v))),
-                        _ => continue,
+                        Err(e) if cause.is_none() => {
+                            cause = match errors::chained_cause(py, &e) {
+                                Some(inner) => Some(inner),
+                                None if !e.is_instance::<exceptions::TypeError>(py) => Some(e),
+                                None => None,
+                            }
+                        }
+                        Err(_) => continue,
                     };
                 }
-                Err(PyErr::from(PyDowncastError))
+                Err(errors::serialization_error(
+                    py,
+                    "No union branch accepts the datum",
+                    cause,
+                ))
+            }
+        }
+        // Plain dicts, the common case, skip the class and model checks.
+        SchemaRs::Record { name, fields, .. }
+            if datum.cast_as::<PyDict>(py).is_err()
+                && (is_registered_instance(py, datum, schema, config)?
+                    || pydantic::is_model(datum.as_ref(py))) =>
+        {
+            let aliases = pydantic::aliases(datum.as_ref(py))?;
+            let keys = config.keys.get(&name.fullname(None));
+            let mut rfields = Vec::with_capacity(fields.len());
+
+            for (i, field) in fields.iter().enumerate() {
+                let value = match (aliases.get(&field.name), keys.and_then(|keys| keys.get(i))) {
+                    (None, Some(key)) => datum.getattr(py, key)?,
+                    (attribute, _) => {
+                        datum.getattr(py, attribute.unwrap_or(&field.name).as_str())?
+                    }
+                };
+                let value = to_avro_field(py, value, field, config, path)?;
+                rfields.push((field.name.clone(), value));
             }
+
+            Ok(Value::Record(rfields))
         }
-        &SchemaRs::Record { ref fields, .. } => {
+        SchemaRs::Record { name, fields, .. } => {
             let record_dict = datum.cast_as::<PyDict>(py)?;
+            let keys = config.keys.get(&name.fullname(None));
             let mut rfields = Vec::with_capacity(fields.len());
 
-            for field in fields.iter() {
-                let keyo = field.name.clone().to_object(py);
-                match record_dict.get_item(keyo) {
+            for (i, field) in fields.iter().enumerate() {
+                let item = match keys.and_then(|keys| keys.get(i)) {
+                    Some(key) => record_dict.get_item(key),
+                    None => record_dict.get_item(field.name.as_str()),
+                };
+                match item {
                     Some(value) => {
-                        let value = to_avro_value(py, &value.to_object(py), &field.schema)?;
+                        let value = to_avro_field(py, value.to_object(py), field, config, path)?;
                         rfields.push((field.name.clone(), value));
                     }
                     None => return Err(PyErr::from(PyDowncastError)),
@@ -182,33 +1300,230 @@ fn to_avro_value(py: Python, datum: &PyObject, schema: &SchemaRs) -> PyResult<Va
 
             Ok(Value::Record(rfields))
         }
-        &SchemaRs::Enum { ref symbols, .. } => {
-            let string = datum.extract::<String>(py);
+        SchemaRs::Enum { name, symbols, .. } => {
+            // Members of `enum.Enum` subclasses are encoded by name.
+            let string = match datum.getattr(py, "_name_") {
+                Ok(name) => name.extract::<String>(py),
+                Err(_) => datum.extract::<String>(py),
+            };
             if let Ok(string) = string {
-                if let Some(index) = symbols.iter().position(|ref item| item == &&string) {
-                    Ok(Value::Enum(index as i32, string))
-                } else {
-                    return Err(PyErr::from(PyDowncastError));
+                let index = match config.symbols.get(&name.fullname(None)) {
+                    Some(indices) => indices.get(&string).copied(),
+                    None => symbols
+                        .iter()
+                        .position(|symbol| symbol == &string)
+                        .map(|index| index as i32),
+                };
+                match index {
+                    Some(index) => Ok(Value::Enum(index, string)),
+                    None => Err(PyErr::from(PyDowncastError)),
                 }
             } else {
                 let index = datum.extract::<i32>(py)? as usize;
                 if index < symbols.len() {
                     Ok(Value::Enum(index as i32, symbols[index].clone()))
                 } else {
-                    return Err(PyErr::from(PyDowncastError));
+                    Err(PyErr::from(PyDowncastError))
                 }
             }
         }
-        &SchemaRs::Fixed { .. } => {
+        SchemaRs::Fixed { .. } => {
             let bytes = datum.extract::<Vec<u8>>(py)?;
             Ok(Value::Fixed(bytes.len(), bytes))
         }
-        _ => Ok(Value::Null),
+        SchemaRs::Date => Ok(Value::Date(logical::date(py, datum)?)),
+        SchemaRs::TimestampMillis => Ok(Value::TimestampMillis(logical::timestamp_millis(
+            py, datum,
+        )?)),
+        SchemaRs::TimestampMicros => Ok(Value::TimestampMicros(logical::timestamp_micros(
+            py, datum,
+        )?)),
+        SchemaRs::Decimal {
+            precision,
+            scale,
+            inner,
+        } => {
+            let size = match **inner {
+                SchemaRs::Fixed { size, .. } => Some(size),
+                _ => None,
+            };
+            let unscaled = logical::decimal(py, datum, *precision, *scale, size)?;
+            Ok(Value::Decimal(unscaled.into()))
+        }
+        _ => Err(PyErr::new::<exceptions::TypeError, _>(format!(
+            "Encoding {} values is not supported",
+            branch_name(schema)
+        ))),
     }
 }
 
+/// Convert the value of a record `field`, applying its encode hook if any.
+fn to_avro_field(
+    py: Python,
+    datum: PyObject,
+    field: &RecordField,
+    config: &Config,
+    parent: Option<&FieldPath>,
+) -> PyResult<Value> {
+    let path = FieldPath::child(parent, &field.name);
+    match config.encode_hooks.get(&path) {
+        Some(hook) => {
+            let datum = hook.call1(py, (datum,))?;
+            to_avro_value(py, &datum, &field.schema, config, Some(&path))
+        }
+        None => to_avro_value(py, &datum, &field.schema, config, Some(&path)),
+    }
+}
+
+/// Encode a Python int as the narrowest of the `int` and `long` union
+/// `variants`, if both are present.
+fn to_avro_integer(py: Python, datum: &PyObject, variants: &[SchemaRs]) -> Option<Value> {
+    let datum = datum.as_ref(py);
+    if datum.cast_as::<PyLong>().is_err() || datum.cast_as::<PyBool>().is_ok() {
+        return None;
+    }
+    if !(variants.contains(&SchemaRs::Int) && variants.contains(&SchemaRs::Long)) {
+        return None;
+    }
+    let n = datum.extract::<i64>().ok()?;
+    match i32::try_from(n) {
+        Ok(n) => Some(Value::Int(n)),
+        Err(_) => Some(Value::Long(n)),
+    }
+}
+
+/// The `(branch, value)` of `datum` if it is such a tuple and `config`
+/// returns unions as tuples.
+fn branch_tuple(py: Python, datum: &PyObject, config: &Config) -> Option<(String, PyObject)> {
+    if !config.union_branches {
+        return None;
+    }
+    datum.cast_as::<PyTuple>(py).ok()?.extract().ok()
+}
+
+/// Whether `datum` is an instance of the class registered for the record `schema`.
+fn is_registered_instance(
+    py: Python,
+    datum: &PyObject,
+    schema: &SchemaRs,
+    config: &Config,
+) -> PyResult<bool> {
+    match schema {
+        SchemaRs::Record { name, .. } => match config.types.get(&name.fullname(None)) {
+            Some(cls) => cls.cast_as::<PyType>(py)?.is_instance(datum),
+            None => Ok(false),
+        },
+        _ => Ok(false),
+    }
+}
+
+/// Derive a record schema from an annotated class: a dataclass, a
+/// `TypedDict` or a pydantic model. `Optional` and unions become unions,
+/// lists and sets arrays, str-keyed dicts maps, `enum.Enum` subclasses
+/// enums, `datetime` `timestamp-micros` and `date` `date`. Derived record
+/// (except `TypedDict`) and enum classes are registered on the schema.
+#[pyfunction]
+fn schema_from_type(py: Python, cls: &PyType) -> PyResult<Schema> {
+    let mut deriver = derive::Deriver::new(py)?;
+    let json = deriver.record(cls)?;
+    match validate::parse_json(&json) {
+        Ok((schema, json)) => Ok(Schema::build(
+            py,
+            Config {
+                types: deriver.types,
+                ..Config::default()
+            },
+            schema,
+            json,
+        )),
+        Err(e) => Err(e.into_pyerr(py)),
+    }
+}
+
+/// Check `schema` against the style `rules`, all of them by default:
+///
+/// - `snake-case-fields`: field names are snake_case,
+/// - `namespaced-types`: named types have a namespace,
+/// - `type-docs` and `field-docs`: named types and fields have a `doc`,
+/// - `field-defaults`: fields have a default,
+/// - `forbidden-types`: none of the primitive `forbidden_types` is used
+///   without a logical type (e.g. `float` for amounts of money).
+///
+/// Returns the `Finding`s in schema order, empty if the schema is clean.
+#[pyfunction(rules = "None", forbidden_types = "vec![\"float\".to_string()]")]
+fn lint_schema(
+    schema: &Schema,
+    rules: Option<Vec<String>>,
+    forbidden_types: Vec<String>,
+) -> PyResult<Vec<Finding>> {
+    let rules = match rules {
+        Some(rules) => rules,
+        None => lint::RULES.iter().map(|rule| rule.to_string()).collect(),
+    };
+    if let Some(rule) = rules
+        .iter()
+        .find(|rule| !lint::RULES.contains(&rule.as_str()))
+    {
+        return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+            "Unknown lint rule: {}",
+            rule
+        )));
+    }
+    let mut linter = lint::Linter {
+        rules: &rules,
+        forbidden: &forbidden_types,
+        findings: Vec::new(),
+    };
+    linter.lint(&schema.json, "$", None);
+    Ok(linter.findings)
+}
+
+/// Check that the writer schema of the container file at `path` is
+/// compatible with `expected_schema` in `mode` (`"BACKWARD"`, `"FORWARD"`
+/// or `"FULL"`), reading the file header only.
+#[pyfunction(mode = "\"BACKWARD\"")]
+fn check_file_schema(
+    path: FsPath,
+    expected_schema: &Schema,
+    mode: &str,
+) -> PyResult<FileSchemaReport> {
+    compat::check(&path.0, expected_schema, mode)
+}
+
+/// Measure how many of the `sample_records` per second `schema` encodes
+/// and decodes, with and without the conversion from and to Python objects,
+/// over `iterations` passes. Registered types and hooks apply.
+#[pyfunction(iterations = "1000")]
+fn bench(
+    py: Python,
+    schema: &Schema,
+    sample_records: Vec<PyObject>,
+    iterations: usize,
+) -> PyResult<Bench> {
+    if sample_records.is_empty() || iterations == 0 {
+        return Err(PyErr::new::<exceptions::ValueError, _>(
+            "bench needs sample records and iterations",
+        ));
+    }
+    bench::run(py, schema, &sample_records, iterations)
+}
+
 #[pymodule]
-fn pyo3avro_rs(_py: Python, m: &PyModule) -> PyResult<()> {
+fn pyo3avro_rs(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<Schema>()?;
+    m.add_wrapped(wrap_pyfunction!(schema_from_type))?;
+    m.add_wrapped(wrap_pyfunction!(lint_schema))?;
+    m.add_wrapped(wrap_pyfunction!(bench))?;
+    m.add_wrapped(wrap_pyfunction!(check_file_schema))?;
+    m.add_class::<Decoder>()?;
+    m.add_class::<DatumIter>()?;
+    m.add_class::<SchemaSet>()?;
+    m.add_class::<Datum>()?;
+    m.add_class::<EncodedBuffer>()?;
+    m.add_class::<Finding>()?;
+    m.add_class::<Bench>()?;
+    m.add_class::<FileSchemaReport>()?;
+    m.add("SchemaParseError", py.get_type::<SchemaParseError>())?;
+    m.add("SerializationError", py.get_type::<SerializationError>())?;
     Ok(())
-}
\ No newline at end of file
+}
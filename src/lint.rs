@@ -0,0 +1,155 @@
+use pyo3::prelude::*;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::resolver::named_type;
+
+/// Rules checked by `lint_schema` when none are selected.
+pub const RULES: [&str; 6] = [
+    "snake-case-fields",
+    "namespaced-types",
+    "type-docs",
+    "field-docs",
+    "field-defaults",
+    "forbidden-types",
+];
+
+/// A schema style violation found by `lint_schema`.
+///
+/// `rule` is the name of the violated rule, `path` the JSON path of the
+/// offending node (e.g. `$.fields[2]`) and `message` a description.
+#[pyclass]
+pub struct Finding {
+    #[pyo3(get)]
+    rule: String,
+    #[pyo3(get)]
+    path: String,
+    #[pyo3(get)]
+    message: String,
+}
+
+#[pymethods]
+impl Finding {
+    fn __repr__(&self) -> String {
+        format!("Finding({}, {}, {})", self.rule, self.path, self.message)
+    }
+}
+
+/// Checks the schema style rules in `rules`, `forbidden-types` rejecting
+/// the primitive types of `forbidden` not annotated with a logical type.
+pub struct Linter<'a> {
+    pub rules: &'a [String],
+    pub forbidden: &'a [String],
+    pub findings: Vec<Finding>,
+}
+
+impl<'a> Linter<'a> {
+    pub fn lint(&mut self, json: &JsonValue, path: &str, namespace: Option<&str>) {
+        match json {
+            JsonValue::String(kind) => self.check_type(kind, path),
+            JsonValue::Array(variants) => {
+                for (i, variant) in variants.iter().enumerate() {
+                    self.lint(variant, &format!("{}[{}]", path, i), namespace);
+                }
+            }
+            JsonValue::Object(object) => self.lint_object(object, path, namespace),
+            _ => {}
+        }
+    }
+
+    fn lint_object(
+        &mut self,
+        object: &Map<String, JsonValue>,
+        path: &str,
+        namespace: Option<&str>,
+    ) {
+        let kind = match object.get("type") {
+            Some(JsonValue::String(kind)) => kind.as_str(),
+            Some(inner) => return self.lint(inner, &format!("{}.type", path), namespace),
+            None => return,
+        };
+        let (fullname, namespace) = match named_type(object, namespace) {
+            Some((fullname, namespace)) => (fullname, namespace),
+            None => {
+                if !object.contains_key("logicalType") {
+                    self.check_type(kind, path);
+                }
+                for key in &["items", "values"] {
+                    if let Some(inner) = object.get(*key) {
+                        self.lint(inner, &format!("{}.{}", path, key), namespace);
+                    }
+                }
+                return;
+            }
+        };
+
+        if namespace.is_none() {
+            self.report(
+                "namespaced-types",
+                path,
+                format!("Type '{}' has no namespace", fullname),
+            );
+        }
+        if !object.contains_key("doc") {
+            self.report("type-docs", path, format!("Type '{}' has no doc", fullname));
+        }
+        let fields = object.get("fields").and_then(JsonValue::as_array);
+        for (i, field) in fields.into_iter().flatten().enumerate() {
+            let path = format!("{}.fields[{}]", path, i);
+            let name = field
+                .get("name")
+                .and_then(JsonValue::as_str)
+                .unwrap_or_default();
+            if !is_snake_case(name) {
+                self.report(
+                    "snake-case-fields",
+                    &path,
+                    format!("Field '{}' of '{}' is not snake_case", name, fullname),
+                );
+            }
+            if field.get("doc").is_none() {
+                self.report(
+                    "field-docs",
+                    &path,
+                    format!("Field '{}' of '{}' has no doc", name, fullname),
+                );
+            }
+            if field.get("default").is_none() {
+                self.report(
+                    "field-defaults",
+                    &path,
+                    format!("Field '{}' of '{}' has no default", name, fullname),
+                );
+            }
+            if let Some(inner) = field.get("type") {
+                self.lint(inner, &format!("{}.type", path), namespace.as_deref());
+            }
+        }
+    }
+
+    fn check_type(&mut self, kind: &str, path: &str) {
+        if self.forbidden.iter().any(|forbidden| forbidden == kind) {
+            self.report(
+                "forbidden-types",
+                path,
+                format!("Type '{}' is forbidden", kind),
+            );
+        }
+    }
+
+    fn report(&mut self, rule: &str, path: &str, message: String) {
+        if self.rules.iter().any(|selected| selected == rule) {
+            self.findings.push(Finding {
+                rule: rule.to_string(),
+                path: path.to_string(),
+                message,
+            });
+        }
+    }
+}
+
+fn is_snake_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
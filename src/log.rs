@@ -0,0 +1,20 @@
+//! Records from the native layer, emitted through Python's `logging` under
+//! the `pyo3avro_rs` logger so they are filtered and routed like any other.
+
+use pyo3::prelude::*;
+
+pub const DEBUG: i32 = 10;
+
+/// Log the message built by `message` at `level`, if the logger is enabled
+/// for it. Logging never fails the operation being logged.
+pub fn log<F: FnOnce() -> String>(py: Python, level: i32, message: F) {
+    let _ = emit(py, level, message);
+}
+
+fn emit<F: FnOnce() -> String>(py: Python, level: i32, message: F) -> PyResult<()> {
+    let logger = py.import("logging")?.call1("getLogger", ("pyo3avro_rs",))?;
+    if logger.call_method1("isEnabledFor", (level,))?.is_true()? {
+        logger.call_method1("log", (level, message()))?;
+    }
+    Ok(())
+}
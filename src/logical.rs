@@ -0,0 +1,216 @@
+use pyo3::exceptions;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyBytes, PyDict, PyLong};
+use pyo3::PyDowncastError;
+
+/// Days from 0001-01-01 (`date.toordinal() == 1`) to the Unix epoch.
+const EPOCH_ORDINAL: i32 = 719_163;
+
+/// Microseconds since the Unix epoch of a `datetime`, naive ones being
+/// taken as UTC. Integers are passed through as already encoded.
+///
+/// Anything with the attributes and methods of a `datetime` is accepted
+/// (subclasses, `pandas.Timestamp`, `arrow.Arrow`...), as well as numpy
+/// `datetime64` values.
+pub fn timestamp_micros(py: Python, datum: &PyObject) -> PyResult<i64> {
+    if let Some(micros) = datetime64(datum.as_ref(py), "us")? {
+        return Ok(micros);
+    }
+    if let Ok(micros) = datum.extract::<i64>(py) {
+        return Ok(micros);
+    }
+    let datum = datum.as_ref(py);
+    let days = i64::from(datum.call_method0("toordinal")?.extract::<i32>()? - EPOCH_ORDINAL);
+    let seconds = datum.getattr("hour")?.extract::<i64>()? * 3_600
+        + datum.getattr("minute")?.extract::<i64>()? * 60
+        + datum.getattr("second")?.extract::<i64>()?;
+    let micros =
+        (days * 86_400 + seconds) * 1_000_000 + datum.getattr("microsecond")?.extract::<i64>()?;
+    let offset = datum.call_method0("utcoffset")?;
+    if offset.is_none() {
+        return Ok(micros);
+    }
+    Ok(micros - timedelta_micros(offset)?)
+}
+
+/// Milliseconds since the Unix epoch of a `datetime`, see `timestamp_micros`.
+pub fn timestamp_millis(py: Python, datum: &PyObject) -> PyResult<i64> {
+    if let Some(millis) = datetime64(datum.as_ref(py), "ms")? {
+        return Ok(millis);
+    }
+    if let Ok(millis) = datum.extract::<i64>(py) {
+        return Ok(millis);
+    }
+    Ok(timestamp_micros(py, datum)?.div_euclid(1_000))
+}
+
+/// Days since the Unix epoch of a `date`, or of anything with a
+/// `toordinal` method. Integers and numpy `datetime64` values are accepted.
+pub fn date(py: Python, datum: &PyObject) -> PyResult<i32> {
+    if let Some(days) = datetime64(datum.as_ref(py), "D")? {
+        return Ok(days as i32);
+    }
+    if let Ok(days) = datum.extract::<i32>(py) {
+        return Ok(days);
+    }
+    Ok(datum.call_method0(py, "toordinal")?.extract::<i32>(py)? - EPOCH_ORDINAL)
+}
+
+/// The count of `unit` since the Unix epoch of a numpy `datetime64`, `None`
+/// for anything else. Checked before integers, which `datetime64` converts
+/// to in its own unit.
+fn datetime64(datum: &PyAny, unit: &str) -> PyResult<Option<i64>> {
+    let is_datetime64 = match datum.getattr("dtype") {
+        Ok(dtype) => dtype.getattr("kind")?.extract::<&str>()? == "M",
+        Err(_) => false,
+    };
+    if !is_datetime64 {
+        return Ok(None);
+    }
+    let value = datum
+        .call_method1("astype", (format!("datetime64[{}]", unit),))?
+        .call_method1("astype", ("int64",))?;
+    Ok(Some(value.extract()?))
+}
+
+/// Microseconds of a `timedelta`.
+fn timedelta_micros(delta: &PyAny) -> PyResult<i64> {
+    let days = delta.getattr("days")?.extract::<i64>()?;
+    let seconds = delta.getattr("seconds")?.extract::<i64>()?;
+    let micros = delta.getattr("microseconds")?.extract::<i64>()?;
+    Ok((days * 86_400 + seconds) * 1_000_000 + micros)
+}
+
+/// Unscaled big-endian two's complement bytes of a decimal `datum` of
+/// `precision` digits, `scale` of them after the point, at most `size`
+/// bytes long if set. `decimal.Decimal` values are scaled, and Python ints
+/// taken as already scaled (the unscaled value).
+pub fn decimal(
+    py: Python,
+    datum: &PyObject,
+    precision: usize,
+    scale: usize,
+    size: Option<usize>,
+) -> PyResult<Vec<u8>> {
+    let datum = datum.as_ref(py);
+    let unscaled = if datum.cast_as::<PyLong>().is_ok() && datum.cast_as::<PyBool>().is_err() {
+        datum
+    } else {
+        let tuple = match datum.call_method0("as_tuple") {
+            Ok(tuple) => tuple,
+            Err(_) => return Err(PyErr::from(PyDowncastError)),
+        };
+        let (sign, mut digits, exponent) = tuple.extract::<(u8, Vec<u8>, i64)>()?;
+        let shift = exponent + scale as i64;
+        if shift < 0 {
+            let kept = digits.len().saturating_sub(shift.unsigned_abs() as usize);
+            if digits[kept..].iter().any(|digit| *digit != 0) {
+                return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                    "{} has more than {} decimal places",
+                    datum, scale
+                )));
+            }
+            digits.truncate(kept);
+        } else if digits.len() + shift as usize > precision {
+            return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                "{} does not fit a decimal of precision {}",
+                datum, precision
+            )));
+        } else {
+            digits.resize(digits.len() + shift as usize, 0);
+        }
+        let mut unscaled = if sign == 1 {
+            "-".to_string()
+        } else {
+            String::new()
+        };
+        unscaled.extend(digits.iter().map(|digit| char::from(b'0' + digit)));
+        if digits.is_empty() {
+            unscaled.push('0');
+        }
+        py.get_type::<PyLong>().call1((unscaled,))?
+    };
+    let (bytes, digits) = match unscaled.extract::<i128>() {
+        Ok(n) => (
+            minimal(&n.to_be_bytes()),
+            n.unsigned_abs().to_string().len(),
+        ),
+        // Beyond 38 digits, let Python lay the bytes out.
+        Err(_) => {
+            let length = (unscaled.call_method0("bit_length")?.extract::<usize>()? + 8) / 8;
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("signed", true)?;
+            let bytes = unscaled.call_method("to_bytes", (length, "big"), Some(kwargs))?;
+            let digits = unscaled.call_method0("__abs__")?.str()?.len()?;
+            (minimal(bytes.cast_as::<PyBytes>()?.as_bytes()), digits)
+        }
+    };
+    if digits > precision || size.is_some_and(|size| bytes.len() > size) {
+        return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+            "{} does not fit a decimal of precision {}",
+            datum, precision
+        )));
+    }
+    Ok(bytes)
+}
+
+/// `bytes` without the leading bytes only repeating the sign.
+fn minimal(bytes: &[u8]) -> Vec<u8> {
+    let redundant = bytes
+        .windows(2)
+        .take_while(|pair| {
+            (pair[0] == 0x00 && pair[1] & 0x80 == 0) || (pair[0] == 0xff && pair[1] & 0x80 != 0)
+        })
+        .count();
+    bytes[redundant..].to_vec()
+}
+
+/// The UTC `datetime` `micros` microseconds after the Unix epoch.
+pub fn to_datetime(py: Python, micros: i64) -> PyResult<PyObject> {
+    let datetime = py.import("datetime")?;
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("tzinfo", datetime.getattr("timezone")?.getattr("utc")?)?;
+    let epoch = datetime.call("datetime", (1970, 1, 1), Some(kwargs))?;
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("microseconds", micros)?;
+    let delta = datetime.call("timedelta", (), Some(kwargs))?;
+    Ok(epoch.call_method1("__add__", (delta,))?.to_object(py))
+}
+
+/// The `date` `days` days after the Unix epoch.
+pub fn to_date(py: Python, days: i32) -> PyResult<PyObject> {
+    let date = py.import("datetime")?.getattr("date")?;
+    Ok(date
+        .call_method1("fromordinal", (days + EPOCH_ORDINAL,))?
+        .to_object(py))
+}
+
+/// The `time` `micros` microseconds after midnight.
+pub fn to_time(py: Python, micros: i64) -> PyResult<PyObject> {
+    let time = py.import("datetime")?.getattr("time")?;
+    let (seconds, micros) = (micros.div_euclid(1_000_000), micros.rem_euclid(1_000_000));
+    let args = (seconds / 3600, seconds / 60 % 60, seconds % 60, micros);
+    Ok(time.call1(args)?.to_object(py))
+}
+
+/// The `uuid.UUID` of its string representation.
+pub fn to_uuid(py: Python, uuid: String) -> PyResult<PyObject> {
+    let uuid_type = py.import("uuid")?.getattr("UUID")?;
+    Ok(uuid_type.call1((uuid,))?.to_object(py))
+}
+
+/// The `decimal.Decimal` of an unscaled big-endian two's complement integer.
+pub fn to_decimal(py: Python, unscaled: &[u8], scale: usize) -> PyResult<PyObject> {
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("signed", true)?;
+    let unscaled = py.get_type::<PyLong>().call_method(
+        "from_bytes",
+        (PyBytes::new(py, unscaled), "big"),
+        Some(kwargs),
+    )?;
+    let decimal = py.import("decimal")?.getattr("Decimal")?;
+    Ok(decimal
+        .call1((unscaled,))?
+        .call_method1("scaleb", (-(scale as i64),))?
+        .to_object(py))
+}
@@ -0,0 +1,123 @@
+//! [MessagePack](https://github.com/msgpack/msgpack/blob/master/spec.md)
+//! serialization of decoded Avro values.
+//!
+//! Records become maps keyed by field name, enums their symbol, unions their
+//! value, and logical types their underlying representation: integers for
+//! dates, times and timestamps, strings for UUIDs, and binaries for decimals
+//! (unscaled big-endian two's complement) and durations.
+
+use std::convert::TryFrom;
+
+use avro_rs::types::Value;
+
+pub fn write(value: &Value, sort_maps: bool, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Boolean(false) => out.push(0xc2),
+        Value::Boolean(true) => out.push(0xc3),
+        Value::Int(n) | Value::Date(n) | Value::TimeMillis(n) => write_int(i64::from(*n), out),
+        Value::Long(n)
+        | Value::TimeMicros(n)
+        | Value::TimestampMillis(n)
+        | Value::TimestampMicros(n) => write_int(*n, out),
+        Value::Float(x) => {
+            out.push(0xca);
+            out.extend_from_slice(&x.to_be_bytes());
+        }
+        Value::Double(x) => {
+            out.push(0xcb);
+            out.extend_from_slice(&x.to_be_bytes());
+        }
+        Value::Bytes(bytes) | Value::Fixed(_, bytes) => write_bin(bytes, out),
+        Value::String(string) | Value::Enum(_, string) => write_str(string, out),
+        Value::Uuid(uuid) => write_str(&uuid.to_string(), out),
+        Value::Decimal(decimal) => write_bin(&Vec::try_from(decimal).unwrap_or_default(), out),
+        Value::Duration(duration) => write_bin(&<[u8; 12]>::from(*duration), out),
+        Value::Union(inner) => write(inner, sort_maps, out),
+        Value::Array(items) => {
+            write_header(items.len(), [0x90, 0xdc, 0xdd], out);
+            for item in items {
+                write(item, sort_maps, out);
+            }
+        }
+        Value::Map(items) => {
+            let mut items = items.iter().collect::<Vec<_>>();
+            if sort_maps {
+                items.sort_by_key(|(key, _)| *key);
+            }
+            write_header(items.len(), [0x80, 0xde, 0xdf], out);
+            for (key, value) in items {
+                write_str(key, out);
+                write(value, sort_maps, out);
+            }
+        }
+        Value::Record(fields) => {
+            write_header(fields.len(), [0x80, 0xde, 0xdf], out);
+            for (name, value) in fields {
+                write_str(name, out);
+                write(value, sort_maps, out);
+            }
+        }
+    }
+}
+
+fn write_int(n: i64, out: &mut Vec<u8>) {
+    match n {
+        0..=0x7f => out.push(n as u8),
+        -32..=-1 => out.push(n as i8 as u8),
+        -0x80..=0x7f => out.extend_from_slice(&[0xd0, n as i8 as u8]),
+        -0x8000..=0x7fff => {
+            out.push(0xd1);
+            out.extend_from_slice(&(n as i16).to_be_bytes());
+        }
+        -0x8000_0000..=0x7fff_ffff => {
+            out.push(0xd2);
+            out.extend_from_slice(&(n as i32).to_be_bytes());
+        }
+        _ => {
+            out.push(0xd3);
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+    }
+}
+
+fn write_str(string: &str, out: &mut Vec<u8>) {
+    let len = string.len();
+    if len <= 0x1f {
+        out.push(0xa0 | len as u8);
+    } else if len <= 0xff {
+        out.extend_from_slice(&[0xd9, len as u8]);
+    } else {
+        write_len(len, [0xda, 0xdb], out);
+    }
+    out.extend_from_slice(string.as_bytes());
+}
+
+fn write_bin(bytes: &[u8], out: &mut Vec<u8>) {
+    if bytes.len() <= 0xff {
+        out.extend_from_slice(&[0xc4, bytes.len() as u8]);
+    } else {
+        write_len(bytes.len(), [0xc5, 0xc6], out);
+    }
+    out.extend_from_slice(bytes);
+}
+
+/// Header of an array or map of `len` items, given its `[fix, 16-bit, 32-bit]` markers.
+fn write_header(len: usize, markers: [u8; 3], out: &mut Vec<u8>) {
+    if len <= 0x0f {
+        out.push(markers[0] | len as u8);
+    } else {
+        write_len(len, [markers[1], markers[2]], out);
+    }
+}
+
+/// A 16-bit or 32-bit length preceded by the matching marker.
+fn write_len(len: usize, markers: [u8; 2], out: &mut Vec<u8>) {
+    if len <= 0xffff {
+        out.push(markers[0]);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.push(markers[1]);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
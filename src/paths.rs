@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+
+use pyo3::prelude::*;
+use pyo3::PyNativeType;
+
+/// A filesystem path given as `str`, `bytes` or `os.PathLike`, converted
+/// through `os.fsencode`/`os.fsdecode` so that paths which are not valid
+/// UTF-8 survive the round trip.
+pub struct FsPath(pub PathBuf);
+
+impl<'a> FromPyObject<'a> for FsPath {
+    #[cfg(unix)]
+    fn extract(path: &'a PyAny) -> PyResult<Self> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let encoded = path.py().import("os")?.call1("fsencode", (path,))?;
+        let bytes = encoded.extract::<&[u8]>()?;
+        Ok(FsPath(std::ffi::OsStr::from_bytes(bytes).into()))
+    }
+
+    #[cfg(not(unix))]
+    fn extract(path: &'a PyAny) -> PyResult<Self> {
+        let decoded = path.py().import("os")?.call1("fsdecode", (path,))?;
+        Ok(FsPath(PathBuf::from(decoded.extract::<String>()?)))
+    }
+}
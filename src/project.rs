@@ -0,0 +1,93 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::Value as JsonValue;
+
+use crate::resolver::named_type;
+
+/// Field paths to keep, as a tree of field names. Fields mapped to `None`
+/// are kept whole.
+#[derive(Default)]
+pub struct Selection(BTreeMap<String, Option<Selection>>);
+
+impl Selection {
+    pub fn insert(&mut self, path: &[&str]) {
+        if let Some((name, rest)) = path.split_first() {
+            let child = self
+                .0
+                .entry(name.to_string())
+                .or_insert_with(|| Some(Selection::default()));
+            match (child, rest) {
+                (child, []) => *child = None,
+                (Some(selection), rest) => selection.insert(rest),
+                // Keeping a field whole wins over keeping some of its fields.
+                (None, _) => {}
+            }
+        }
+    }
+}
+
+/// The JSON of `json` keeping only the record fields in `selection`, arrays,
+/// maps and unions being transparent. Names and namespaces are unchanged, so
+/// a named type used at several paths must be selected alike at each.
+pub fn project(json: &JsonValue, selection: &Selection) -> Result<JsonValue, String> {
+    project_in(json, selection, None, &mut HashMap::new())
+}
+
+fn project_in(
+    json: &JsonValue,
+    selection: &Selection,
+    namespace: Option<&str>,
+    projected: &mut HashMap<String, JsonValue>,
+) -> Result<JsonValue, String> {
+    match json {
+        JsonValue::Array(variants) => variants
+            .iter()
+            .map(|variant| project_in(variant, selection, namespace, projected))
+            .collect::<Result<_, _>>()
+            .map(JsonValue::Array),
+        JsonValue::Object(object) => {
+            let named = named_type(object, namespace);
+            let namespace = match &named {
+                Some((_, namespace)) => namespace.as_deref(),
+                None => namespace,
+            };
+            let mut object = object.clone();
+            if let Some(JsonValue::Array(fields)) = object.get_mut("fields") {
+                fields.retain(|field| {
+                    let name = field.get("name").and_then(JsonValue::as_str);
+                    name.is_some_and(|name| selection.0.contains_key(name))
+                });
+                for field in fields.iter_mut() {
+                    let name = field["name"].as_str().unwrap_or_default();
+                    if let Some(Some(child)) = selection.0.get(name) {
+                        field["type"] = project_in(&field["type"], child, namespace, projected)?;
+                    }
+                }
+            } else {
+                for key in &["items", "values", "type"] {
+                    if let Some(inner) = object.get(*key) {
+                        let inner = project_in(inner, selection, namespace, projected)?;
+                        object.insert(key.to_string(), inner);
+                    }
+                }
+            }
+            let object = JsonValue::Object(object);
+            if let Some((fullname, _)) = named {
+                match projected.get(&fullname) {
+                    Some(known) if *known != object => {
+                        return Err(format!(
+                            "{} is used at several paths with different fields selected",
+                            fullname
+                        ))
+                    }
+                    Some(_) => {}
+                    None => {
+                        projected.insert(fullname, object.clone());
+                    }
+                }
+            }
+            Ok(object)
+        }
+        _ => Ok(json.clone()),
+    }
+}
@@ -0,0 +1,59 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyType};
+use pyo3::{AsPyPointer, PyNativeType};
+
+/// Number of types `MODEL_TYPES` holds before being cleared.
+const MODEL_TYPES_LIMIT: usize = 256;
+
+thread_local! {
+    /// Whether each type recently seen by `is_model` is a pydantic model,
+    /// keyed by the address of the type, kept alive so that the address is
+    /// not reused. Cleared when full, so that types created on the fly are
+    /// not kept alive forever.
+    static MODEL_TYPES: RefCell<HashMap<usize, (PyObject, bool)>> = RefCell::new(HashMap::new());
+}
+
+/// Whether `datum` is a pydantic (v2) model instance or class.
+pub fn is_model(datum: &PyAny) -> bool {
+    let cls = match datum.cast_as::<PyType>() {
+        Ok(cls) => cls,
+        Err(_) => datum.get_type(),
+    };
+    let key = cls.as_ptr() as usize;
+    if let Some(model) = MODEL_TYPES.with(|types| types.borrow().get(&key).map(|(_, model)| *model))
+    {
+        return model;
+    }
+    let model = cls.hasattr("model_fields").unwrap_or(false)
+        && cls.hasattr("model_validate").unwrap_or(false);
+    MODEL_TYPES.with(|types| {
+        let mut types = types.borrow_mut();
+        if types.len() >= MODEL_TYPES_LIMIT {
+            types.clear();
+        }
+        types.insert(key, (cls.to_object(datum.py()), model))
+    });
+    model
+}
+
+/// Attribute names of the fields of the pydantic model `datum`, keyed by
+/// their alias. Empty for anything else.
+pub fn aliases(datum: &PyAny) -> PyResult<HashMap<String, String>> {
+    let mut aliases = HashMap::new();
+    if is_model(datum) {
+        for (name, info) in model_fields(datum.get_type())? {
+            let alias = info.getattr("alias")?;
+            if !alias.is_none() {
+                aliases.insert(alias.extract()?, name.extract()?);
+            }
+        }
+    }
+    Ok(aliases)
+}
+
+pub fn model_fields(model: &PyType) -> PyResult<&PyDict> {
+    Ok(model.getattr("model_fields")?.cast_as::<PyDict>()?)
+}
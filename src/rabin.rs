@@ -0,0 +1,25 @@
+/// 64-bit Rabin fingerprint (CRC-64-AVRO), as described in the
+/// [Avro specification](https://avro.apache.org/docs/current/spec.html#schema_fingerprints).
+///
+/// avro-rs 0.10 only ships digest-based fingerprints, while single object
+/// encoding requires this one.
+const EMPTY: u64 = 0xc15d_213a_a4d7_a795;
+
+fn table() -> [u64; 256] {
+    let mut table = [0; 256];
+    for (i, entry) in table.iter_mut().enumerate() {
+        let mut fp = i as u64;
+        for _ in 0..8 {
+            fp = (fp >> 1) ^ (EMPTY & (fp & 1).wrapping_neg());
+        }
+        *entry = fp;
+    }
+    table
+}
+
+pub fn fingerprint(data: &[u8]) -> u64 {
+    let table = table();
+    data.iter().fold(EMPTY, |fp, byte| {
+        (fp >> 8) ^ table[((fp ^ u64::from(*byte)) & 0xff) as usize]
+    })
+}
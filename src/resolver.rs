@@ -0,0 +1,397 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use avro_rs::Schema;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::errors::ParseError;
+use crate::validate::{closest, validate};
+
+pub const PRIMITIVES: [&str; 8] = [
+    "null", "boolean", "int", "long", "float", "double", "bytes", "string",
+];
+
+pub const COMPLEX: [&str; 6] = ["record", "error", "enum", "fixed", "array", "map"];
+
+/// A named type found in one of the search directories, not resolved yet.
+struct Source {
+    file: PathBuf,
+    path: String,
+    definition: Map<String, JsonValue>,
+    namespace: Option<String>,
+    /// Another file defining the same full name differently, if any.
+    conflict: Option<PathBuf>,
+}
+
+/// Inlines references to named types in JSON schemas.
+///
+/// avro-rs 0.10 cannot parse a schema referring to a named type by name, so
+/// every reference is replaced by the full definition of the type, taken
+/// either from earlier in the same document or from the `.avsc` files of the
+/// search directories. Inherited namespaces are made explicit on the way, as
+/// avro-rs does not propagate them either.
+///
+/// Files of the search directories that cannot be read or parsed are only
+/// reported when a reference cannot be resolved, as they may define it.
+pub struct Resolver {
+    sources: HashMap<String, Source>,
+    resolved: HashMap<String, JsonValue>,
+    stack: Vec<String>,
+    unreadable: Vec<ParseError>,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Resolver {
+            sources: HashMap::new(),
+            resolved: HashMap::new(),
+            stack: Vec::new(),
+            unreadable: Vec::new(),
+        }
+    }
+
+    /// Index the named types defined by the `.avsc` files of `directory`,
+    /// and of its subdirectories if `recursive` is set, in file name order.
+    pub fn add_directory(&mut self, directory: &Path, recursive: bool) -> Result<(), ParseError> {
+        let location = directory.display().to_string();
+        let mut files = fs::read_dir(directory)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|e| ParseError::new(e.to_string()).in_file(&location))?;
+        files.sort();
+        for file in files {
+            if file.is_dir() {
+                if recursive {
+                    self.add_directory(&file, true)?;
+                }
+            } else if file.extension().and_then(|extension| extension.to_str()) == Some("avsc") {
+                match read_json(&file) {
+                    Ok(json) => self.index(&file, &json, "$", None),
+                    Err(e) => self.unreadable.push(e),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn index(&mut self, file: &Path, json: &JsonValue, path: &str, namespace: Option<&str>) {
+        match json {
+            JsonValue::Array(variants) => {
+                for (i, variant) in variants.iter().enumerate() {
+                    self.index(file, variant, &format!("{}[{}]", path, i), namespace);
+                }
+            }
+            JsonValue::Object(object) => {
+                let mut namespace = namespace.map(|namespace| namespace.to_string());
+                if let Some((fullname, own_namespace)) = named_type(object, namespace.as_deref()) {
+                    let source = self.sources.entry(fullname).or_insert_with(|| Source {
+                        file: file.to_path_buf(),
+                        path: path.to_string(),
+                        definition: object.clone(),
+                        namespace: namespace.clone(),
+                        conflict: None,
+                    });
+                    if (&source.definition, &source.namespace) != (object, &namespace) {
+                        source.conflict.get_or_insert_with(|| file.to_path_buf());
+                    }
+                    namespace = own_namespace;
+                }
+                for key in &["type", "items", "values"] {
+                    if let Some(inner) = object.get(*key) {
+                        let path = format!("{}.{}", path, key);
+                        self.index(file, inner, &path, namespace.as_deref());
+                    }
+                }
+                if let Some(JsonValue::Array(fields)) = object.get("fields") {
+                    for (i, field) in fields.iter().enumerate() {
+                        if let Some(inner) = field.get("type") {
+                            let path = format!("{}.fields[{}].type", path, i);
+                            self.index(file, inner, &path, namespace.as_deref());
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Return `json` (found at the JSON `path`) with all named type
+    /// references inlined.
+    pub fn resolve(
+        &mut self,
+        json: &JsonValue,
+        path: &str,
+        namespace: Option<&str>,
+    ) -> Result<JsonValue, ParseError> {
+        match json {
+            JsonValue::String(name) if PRIMITIVES.contains(&name.as_str()) => Ok(json.clone()),
+            JsonValue::String(name) => self.reference(name, path, namespace),
+            JsonValue::Array(variants) => variants
+                .iter()
+                .enumerate()
+                .map(|(i, variant)| self.resolve(variant, &format!("{}[{}]", path, i), namespace))
+                .collect::<Result<Vec<_>, _>>()
+                .map(JsonValue::Array),
+            JsonValue::Object(object) => self.resolve_object(object, path, namespace),
+            _ => Ok(json.clone()),
+        }
+    }
+
+    fn resolve_object(
+        &mut self,
+        object: &Map<String, JsonValue>,
+        path: &str,
+        namespace: Option<&str>,
+    ) -> Result<JsonValue, ParseError> {
+        let mut object = object.clone();
+        let (fullname, namespace) = match named_type(&object, namespace) {
+            Some((fullname, namespace)) => (Some(fullname), namespace),
+            None => (None, namespace.map(|namespace| namespace.to_string())),
+        };
+
+        if let Some(fullname) = &fullname {
+            if let Some(namespace) = &namespace {
+                object
+                    .entry("namespace")
+                    .or_insert_with(|| JsonValue::String(namespace.clone()));
+            }
+            self.stack.push(fullname.clone());
+        }
+
+        for key in &["items", "values"] {
+            if let Some(inner) = object.get(*key) {
+                let path = format!("{}.{}", path, key);
+                let inner = self.resolve(inner, &path, namespace.as_deref())?;
+                object.insert(key.to_string(), inner);
+            }
+        }
+        if let Some(JsonValue::Array(fields)) = object.get_mut("fields") {
+            for (i, field) in fields.iter_mut().enumerate() {
+                if let Some(inner) = field.get("type") {
+                    let path = format!("{}.fields[{}].type", path, i);
+                    let inner = self.resolve(inner, &path, namespace.as_deref())?;
+                    field["type"] = inner;
+                }
+            }
+        }
+        match object.get("type") {
+            Some(JsonValue::String(kind)) if COMPLEX.contains(&kind.as_str()) => {}
+            Some(inner) => {
+                let path = format!("{}.type", path);
+                let inner = self.resolve(inner, &path, namespace.as_deref())?;
+                object.insert("type".to_string(), inner);
+            }
+            None => {}
+        }
+
+        let object = JsonValue::Object(object);
+        if let Some(fullname) = fullname {
+            self.stack.pop();
+            self.resolved.insert(fullname, object.clone());
+        }
+        Ok(object)
+    }
+
+    fn reference(
+        &mut self,
+        name: &str,
+        path: &str,
+        namespace: Option<&str>,
+    ) -> Result<JsonValue, ParseError> {
+        let mut candidates = Vec::new();
+        if let (false, Some(namespace)) = (name.contains('.'), namespace) {
+            candidates.push(format!("{}.{}", namespace, name));
+        }
+        candidates.push(name.to_string());
+
+        for fullname in candidates {
+            if self.stack.contains(&fullname) {
+                return Err(ParseError::new(format!(
+                    "Recursive reference to {} is not supported",
+                    fullname
+                ))
+                .at(path));
+            }
+            if let Some(definition) = self.resolved.get(&fullname) {
+                return Ok(definition.clone());
+            }
+            if let Some(source) = self.sources.remove(&fullname) {
+                let file = source.file.display().to_string();
+                if let Some(conflict) = source.conflict {
+                    return Err(ParseError::new(format!(
+                        "{} is defined differently in {} and {}",
+                        fullname,
+                        file,
+                        conflict.display()
+                    ))
+                    .at(path));
+                }
+                return self
+                    .resolve_object(
+                        &source.definition,
+                        &source.path,
+                        source.namespace.as_deref(),
+                    )
+                    .map_err(|e| e.in_file(&file));
+            }
+        }
+
+        if !self.unreadable.is_empty() {
+            return Err(self.unreadable.remove(0));
+        }
+        let known = PRIMITIVES
+            .iter()
+            .chain(COMPLEX.iter())
+            .map(|known| known.to_string())
+            .chain(self.sources.keys().cloned())
+            .chain(self.resolved.keys().cloned());
+        Err(ParseError::new(format!("Unknown type '{}'", name))
+            .at(path)
+            .suggest(closest(name, known)))
+    }
+}
+
+/// Inverse of `Resolver::resolve`: `json` with each named type defined at
+/// its first occurrence only, and referred to by full name afterwards.
+/// Fails if a full name has several different definitions, which no
+/// reference could stand for.
+pub fn with_references(json: &JsonValue) -> Result<JsonValue, String> {
+    references(json, None, &mut HashMap::new())
+}
+
+fn references<'a>(
+    json: &'a JsonValue,
+    namespace: Option<&str>,
+    defined: &mut HashMap<String, &'a JsonValue>,
+) -> Result<JsonValue, String> {
+    let object = match json {
+        JsonValue::Array(variants) => {
+            return variants
+                .iter()
+                .map(|variant| references(variant, namespace, defined))
+                .collect::<Result<_, _>>()
+                .map(JsonValue::Array)
+        }
+        JsonValue::Object(object) => object,
+        _ => return Ok(json.clone()),
+    };
+    let (fullname, namespace) = match named_type(object, namespace) {
+        Some((fullname, namespace)) => (Some(fullname), namespace),
+        None => (None, namespace.map(|namespace| namespace.to_string())),
+    };
+    if let Some(fullname) = fullname {
+        match defined.get(&fullname) {
+            Some(definition) if *definition == json => return Ok(JsonValue::String(fullname)),
+            Some(_) => return Err(format!("{} has several different definitions", fullname)),
+            None => {
+                defined.insert(fullname, json);
+            }
+        }
+    }
+    let mut out = object.clone();
+    for key in &["items", "values"] {
+        if let Some(inner) = object.get(*key) {
+            let inner = references(inner, namespace.as_deref(), defined)?;
+            out.insert(key.to_string(), inner);
+        }
+    }
+    if let Some(JsonValue::Array(fields)) = object.get("fields") {
+        let fields = fields
+            .iter()
+            .map(|field| {
+                let mut out = field.clone();
+                if let Some(inner) = field.get("type") {
+                    out["type"] = references(inner, namespace.as_deref(), defined)?;
+                }
+                Ok(out)
+            })
+            .collect::<Result<_, String>>()?;
+        out.insert("fields".to_string(), JsonValue::Array(fields));
+    }
+    match object.get("type") {
+        Some(JsonValue::String(_)) | None => {}
+        Some(inner) => {
+            let inner = references(inner, namespace.as_deref(), defined)?;
+            out.insert("type".to_string(), inner);
+        }
+    }
+    Ok(JsonValue::Object(out))
+}
+
+/// Full name and namespace of a `record`, `error`, `enum` or `fixed` definition.
+pub fn named_type(
+    object: &Map<String, JsonValue>,
+    namespace: Option<&str>,
+) -> Option<(String, Option<String>)> {
+    match object.get("type").and_then(JsonValue::as_str) {
+        Some("record") | Some("error") | Some("enum") | Some("fixed") => {}
+        _ => return None,
+    }
+    let name = object.get("name").and_then(JsonValue::as_str)?;
+    if let Some(index) = name.rfind('.') {
+        return Some((name.to_string(), Some(name[..index].to_string())));
+    }
+    let namespace = object
+        .get("namespace")
+        .and_then(JsonValue::as_str)
+        .or(namespace)
+        .filter(|namespace| !namespace.is_empty());
+    match namespace {
+        Some(namespace) => Some((
+            format!("{}.{}", namespace, name),
+            Some(namespace.to_string()),
+        )),
+        None => Some((name.to_string(), None)),
+    }
+}
+
+/// Read and parse a JSON file, locating syntax errors by file, line and column.
+pub fn read_json(file: &Path) -> Result<JsonValue, ParseError> {
+    let location = file.display().to_string();
+    let input =
+        fs::read_to_string(file).map_err(|e| ParseError::new(e.to_string()).in_file(&location))?;
+    serde_json::from_str(&input).map_err(|e| ParseError::from(e).in_file(&location))
+}
+
+/// Parse the schemas of `paths`, resolving named type references against
+/// earlier files, the `.avsc` files under `search_paths` and the sibling
+/// `.avsc` files of each path. Each schema comes with its resolved JSON.
+pub fn parse_files(
+    paths: &[PathBuf],
+    search_paths: &[PathBuf],
+) -> Result<Vec<(Schema, JsonValue)>, ParseError> {
+    let mut resolver = Resolver::new();
+    for directory in search_paths {
+        resolver.add_directory(directory, true)?;
+    }
+    let mut siblings = paths
+        .iter()
+        .map(|path| match path.parent() {
+            Some(parent) if parent != Path::new("") => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        })
+        .collect::<Vec<_>>();
+    siblings.dedup();
+    for directory in siblings {
+        resolver.add_directory(&directory, false)?;
+    }
+
+    paths
+        .iter()
+        .map(|path| {
+            let json = read_json(path)?;
+            let path = &path.display().to_string();
+            let json = resolver
+                .resolve(&json, "$", None)
+                .map_err(|e| e.in_file(path))?;
+            validate(&json).map_err(|e| e.in_file(path))?;
+            let schema = Schema::parse(&json)
+                .map_err(|e| ParseError::new(e.as_fail().to_string()).in_file(path))?;
+            Ok((schema, json))
+        })
+        .collect()
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use pyo3::exceptions;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::decoder::{split_header, SchemaRef};
+use crate::{to_pyobject, Schema};
+
+/// Versions of a schema, decoding framed messages written with any of them
+/// as the newest one.
+///
+/// Versions are keyed by their fingerprint, and by registry id when added
+/// with one. Single object encoded and schema registry framed messages are
+/// decoded with their writer version and resolved against the newest
+/// (last added) version, whose settings and registered types apply.
+#[pyclass]
+pub struct SchemaSet {
+    versions: HashMap<SchemaRef, Py<Schema>>,
+    newest: Option<Py<Schema>>,
+}
+
+#[pymethods]
+impl SchemaSet {
+    /// `schemas` are the versions, oldest first.
+    #[new]
+    #[args(schemas = "Vec::new()")]
+    fn new(py: Python, schemas: Vec<Py<Schema>>) -> Self {
+        let mut set = SchemaSet {
+            versions: HashMap::new(),
+            newest: None,
+        };
+        for schema in schemas {
+            set.insert(py, schema, None);
+        }
+        set
+    }
+
+    /// Add `schema` as the newest version, also keyed by `registry_id` if given.
+    #[args(registry_id = "None")]
+    fn add(&mut self, py: Python, schema: Py<Schema>, registry_id: Option<u32>) {
+        self.insert(py, schema, registry_id);
+    }
+
+    fn read(&self, py: Python, message: &PyBytes) -> PyResult<PyObject> {
+        let (reference, payload) = split_header(message.as_bytes())?;
+        let writer = match self.versions.get(&reference) {
+            Some(writer) => writer.borrow(py),
+            None => {
+                return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                    "Unknown writer schema with {}",
+                    reference
+                )))
+            }
+        };
+        let reader = match &self.newest {
+            Some(reader) => reader.borrow(py),
+            None => {
+                return Err(PyErr::new::<exceptions::ValueError, _>(
+                    "SchemaSet has no schemas",
+                ))
+            }
+        };
+        let value = reader.decode_with(
+            py,
+            payload,
+            &reader.writer_schema(&writer),
+            Some(reader.reader_schema()),
+        )?;
+        to_pyobject(py, value, &reader.schema, &reader.config, None)
+    }
+}
+
+impl SchemaSet {
+    fn insert(&mut self, py: Python, schema: Py<Schema>, registry_id: Option<u32>) {
+        let fingerprint = schema.borrow(py).fingerprint_bytes();
+        self.versions
+            .insert(SchemaRef::Fingerprint(fingerprint), schema.clone_ref(py));
+        if let Some(id) = registry_id {
+            self.versions
+                .insert(SchemaRef::Id(id), schema.clone_ref(py));
+        }
+        self.newest = Some(schema);
+    }
+}
@@ -0,0 +1,136 @@
+//! Estimation of the CPython heap size of decoded values, as reported by
+//! `sys.getsizeof` on 64-bit builds, and computation of the size of encoded
+//! values.
+
+use std::convert::TryFrom;
+
+use avro_rs::types::Value;
+use avro_rs::Schema;
+
+const LIST: usize = 56;
+const DICT: usize = 64;
+/// Average cost of a dict entry, hash table slack included.
+const DICT_ENTRY: usize = 32;
+const POINTER: usize = 8;
+const STR: usize = 49;
+const BYTES: usize = 33;
+const FLOAT: usize = 24;
+
+/// Approximate number of bytes the Python objects built by decoding `value`
+/// take. `None` and booleans are singletons and cost nothing; records are
+/// counted as dicts.
+pub fn estimate(value: &Value) -> usize {
+    match value {
+        Value::Null | Value::Boolean(_) => 0,
+        Value::Int(n) => int(i64::from(*n)),
+        Value::Long(n) => int(*n),
+        Value::Float(_) | Value::Double(_) => FLOAT,
+        Value::Bytes(bytes) | Value::Fixed(_, bytes) => BYTES + bytes.len(),
+        Value::String(string) | Value::Enum(_, string) => STR + string.len(),
+        Value::Union(inner) => estimate(inner),
+        Value::Array(items) => {
+            LIST + items
+                .iter()
+                .map(|item| POINTER + estimate(item))
+                .sum::<usize>()
+        }
+        Value::Map(items) => {
+            DICT + items
+                .iter()
+                .map(|(key, value)| DICT_ENTRY + STR + key.len() + estimate(value))
+                .sum::<usize>()
+        }
+        Value::Record(fields) => {
+            DICT + fields
+                .iter()
+                .map(|(_, value)| DICT_ENTRY + estimate(value))
+                .sum::<usize>()
+        }
+        Value::Date(_) => 32,
+        Value::TimeMillis(_) | Value::TimeMicros(_) => 40,
+        Value::TimestampMillis(_) | Value::TimestampMicros(_) => 48,
+        Value::Uuid(_) => 56,
+        Value::Decimal(_) => 104,
+        Value::Duration(_) => BYTES + 12,
+    }
+}
+
+/// Size of an int: 28 bytes plus 4 per 30-bit digit beyond the first.
+fn int(n: i64) -> usize {
+    let bits = 64 - n.unsigned_abs().leading_zeros() as usize;
+    28 + 4 * (bits.saturating_sub(1) / 30)
+}
+
+/// Number of bytes of the Avro binary encoding of `value`, a value valid
+/// for `schema`, computed without encoding it.
+pub fn encoded(value: &Value, schema: &Schema) -> usize {
+    match (value, schema) {
+        (Value::Null, _) => 0,
+        (Value::Boolean(_), _) => 1,
+        (Value::Int(n), _) | (Value::Date(n), _) | (Value::TimeMillis(n), _) => {
+            varint(i64::from(*n))
+        }
+        (Value::Long(n), _)
+        | (Value::TimestampMillis(n), _)
+        | (Value::TimestampMicros(n), _)
+        | (Value::TimeMicros(n), _) => varint(*n),
+        (Value::Float(_), _) => 4,
+        (Value::Double(_), _) => 8,
+        (Value::Bytes(bytes), _) => prefixed(bytes.len()),
+        (Value::String(string), _) => prefixed(string.len()),
+        (Value::Uuid(uuid), _) => prefixed(uuid.to_string().len()),
+        (Value::Fixed(size, _), _) => *size,
+        (Value::Enum(index, _), _) => varint(i64::from(*index)),
+        (Value::Duration(_), _) => 12,
+        (Value::Decimal(decimal), Schema::Decimal { inner, .. }) => match &**inner {
+            Schema::Fixed { size, .. } => *size,
+            _ => prefixed(Vec::try_from(decimal).map_or(0, |bytes| bytes.len())),
+        },
+        (Value::Union(item), Schema::Union(inner)) => match inner.find_schema(item) {
+            Some((index, variant)) => varint(index as i64) + encoded(item, variant),
+            None => 0,
+        },
+        (Value::Array(items), Schema::Array(inner)) => {
+            block(items.len()) + items.iter().map(|item| encoded(item, inner)).sum::<usize>()
+        }
+        (Value::Map(items), Schema::Map(inner)) => {
+            block(items.len())
+                + items
+                    .iter()
+                    .map(|(key, value)| prefixed(key.len()) + encoded(value, inner))
+                    .sum::<usize>()
+        }
+        (
+            Value::Record(fields),
+            Schema::Record {
+                fields: rfields, ..
+            },
+        ) => fields
+            .iter()
+            .zip(rfields)
+            .map(|((_, value), field)| encoded(value, &field.schema))
+            .sum(),
+        // Not valid for the schema, not encoded by avro-rs either.
+        _ => 0,
+    }
+}
+
+/// Size of a zig-zag encoded variable-length long.
+fn varint(n: i64) -> usize {
+    let zigzag = ((n << 1) ^ (n >> 63)) as u64;
+    let bits = 64 - zigzag.leading_zeros() as usize;
+    bits.saturating_sub(1) / 7 + 1
+}
+
+/// Size of `len` bytes prefixed with their length.
+fn prefixed(len: usize) -> usize {
+    varint(len as i64) + len
+}
+
+/// Size of the block count and terminating zero of an array or map of `len` items.
+fn block(len: usize) -> usize {
+    match len {
+        0 => 1,
+        len => varint(len as i64) + 1,
+    }
+}
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use avro_rs::Schema as SchemaRs;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple, PyType};
+
+/// Base of the generated record classes: field values live in slots, and
+/// instances compare and print like dataclasses.
+const BASE: &str = r#"
+class Record:
+    __slots__ = ()
+
+    def __init__(self, **fields):
+        for name, value in fields.items():
+            setattr(self, name, value)
+
+    def __eq__(self, other):
+        if type(self) is not type(other):
+            return NotImplemented
+        return all(getattr(self, name) == getattr(other, name) for name in self.__slots__)
+
+    def __repr__(self):
+        fields = ", ".join("%s=%r" % (name, getattr(self, name)) for name in self.__slots__)
+        return "%s(%s)" % (type(self).__name__, fields)
+"#;
+
+/// Generate a `__slots__` class for each record type of `schema`, keyed by
+/// record full name.
+pub fn classes(py: Python, schema: &SchemaRs) -> PyResult<HashMap<String, PyObject>> {
+    let globals = PyDict::new(py);
+    py.run(BASE, Some(globals), None)?;
+    let base = globals.get_item("Record").map(|base| base.to_object(py));
+    let mut classes = HashMap::new();
+    if let Some(base) = base {
+        generate(py, schema, &base, &mut classes)?;
+    }
+    Ok(classes)
+}
+
+fn generate(
+    py: Python,
+    schema: &SchemaRs,
+    base: &PyObject,
+    classes: &mut HashMap<String, PyObject>,
+) -> PyResult<()> {
+    match schema {
+        SchemaRs::Array(inner) | SchemaRs::Map(inner) => generate(py, inner, base, classes)?,
+        SchemaRs::Union(inner) => {
+            for variant in inner.variants() {
+                generate(py, variant, base, classes)?;
+            }
+        }
+        SchemaRs::Record { name, fields, .. } => {
+            let names = fields.iter().map(|field| field.name.as_str());
+            let namespace = PyDict::new(py);
+            namespace.set_item("__slots__", PyTuple::new(py, names))?;
+            namespace.set_item("__module__", "pyo3avro_rs")?;
+            let bases = PyTuple::new(py, [base]);
+            let cls = py
+                .get_type::<PyType>()
+                .call1((&name.name, bases, namespace))?;
+            classes.insert(name.fullname(None), cls.to_object(py));
+            for field in fields {
+                generate(py, &field.schema, base, classes)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use avro_rs::types::Value;
+use avro_rs::{from_avro_datum, Schema as SchemaRs};
+use pyo3::exceptions;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use pyo3::PyIterProtocol;
+
+use crate::{to_pyobject, Schema};
+
+/// Position in a buffer of datums written back to back, as read by
+/// `Schema.read_stream` and `Schema.read_iter`.
+pub struct Cursor {
+    offset: usize,
+    index: usize,
+    count: Option<usize>,
+    length: bool,
+}
+
+impl Cursor {
+    /// Start reading `buffer`, consuming the count when `prefix` is
+    /// `"count"`.
+    pub fn new(buffer: &[u8], prefix: Option<&str>) -> PyResult<Self> {
+        let mut bytes = buffer;
+        let count = match prefix {
+            Some("count") => Some(read_prefix(&mut bytes)?),
+            Some("length") | None => None,
+            Some(prefix) => {
+                return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                    "Unknown prefix: {}",
+                    prefix
+                )))
+            }
+        };
+        Ok(Cursor {
+            offset: buffer.len() - bytes.len(),
+            index: 0,
+            count,
+            length: prefix == Some("length"),
+        })
+    }
+
+    /// Number of datums left, bounded by the buffer size in case of a
+    /// corrupt count.
+    pub fn remaining(&self, buffer: &[u8]) -> usize {
+        let left = buffer.len() - self.offset;
+        self.count.map_or(0, |count| count - self.index).min(left)
+    }
+
+    /// Decode the next datum of `buffer`, or `None` past the last one.
+    pub fn next(&mut self, schema: &SchemaRs, buffer: &[u8]) -> PyResult<Option<Value>> {
+        let mut bytes = &buffer[self.offset..];
+        if self
+            .count
+            .map_or(bytes.is_empty(), |count| self.index >= count)
+        {
+            return Ok(None);
+        }
+        let value = if self.length {
+            let length = read_prefix(&mut bytes)?;
+            if length > bytes.len() {
+                return Err(PyErr::new::<exceptions::ValueError, _>(format!(
+                    "Datum {} is truncated",
+                    self.index
+                )));
+            }
+            let (mut datum, rest) = bytes.split_at(length);
+            bytes = rest;
+            from_avro_datum(schema, &mut datum, None)
+        } else {
+            let remaining = bytes.len();
+            let value = from_avro_datum(schema, &mut bytes, None);
+            if self.count.is_none() && bytes.len() == remaining {
+                return Err(PyErr::new::<exceptions::ValueError, _>(
+                    "Datums of an empty encoding need a count prefix",
+                ));
+            }
+            value
+        };
+        let value = value.map_err(|e| {
+            PyErr::new::<exceptions::ValueError, _>(format!(
+                "Failed to decode datum {}: {}",
+                self.index,
+                e.as_fail()
+            ))
+        })?;
+        self.offset = buffer.len() - bytes.len();
+        self.index += 1;
+        Ok(Some(value))
+    }
+}
+
+/// Read a count or length, an Avro `long`, from the start of `bytes`.
+fn read_prefix(bytes: &mut &[u8]) -> PyResult<usize> {
+    match from_avro_datum(&SchemaRs::Long, bytes, None) {
+        Ok(Value::Long(n)) if n >= 0 => Ok(n as usize),
+        _ => Err(PyErr::new::<exceptions::ValueError, _>(
+            "Failed to decode prefix",
+        )),
+    }
+}
+
+/// Iterator returned by `Schema.read_iter`, decoding one datum per step so
+/// that callers stopping early do not pay for the rest of the buffer.
+#[pyclass]
+pub struct DatumIter {
+    schema: Py<Schema>,
+    buffer: Py<PyBytes>,
+    cursor: Cursor,
+}
+
+impl DatumIter {
+    pub(crate) fn new(
+        py: Python,
+        schema: Py<Schema>,
+        buffer: Py<PyBytes>,
+        prefix: Option<&str>,
+    ) -> PyResult<Self> {
+        let cursor = Cursor::new(buffer.as_ref(py).as_bytes(), prefix)?;
+        Ok(DatumIter {
+            schema,
+            buffer,
+            cursor,
+        })
+    }
+}
+
+#[pyproto]
+impl PyIterProtocol for DatumIter {
+    fn __iter__(slf: PyRef<Self>) -> Py<DatumIter> {
+        slf.into()
+    }
+
+    fn __next__(mut slf: PyRefMut<Self>) -> PyResult<Option<PyObject>> {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let schema = slf.schema.clone_ref(py);
+        let schema = schema.borrow(py);
+        let buffer = slf.buffer.clone_ref(py);
+        match slf
+            .cursor
+            .next(schema.reader_schema(), buffer.as_ref(py).as_bytes())?
+        {
+            Some(value) => Ok(Some(to_pyobject(
+                py,
+                value,
+                &schema.schema,
+                &schema.config,
+                None,
+            )?)),
+            None => Ok(None),
+        }
+    }
+}
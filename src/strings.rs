@@ -0,0 +1,55 @@
+use serde_json::Value as JsonValue;
+
+/// The JSON of `json` with `string` types read as `bytes`, so strings that
+/// are not valid UTF-8 can be decoded. Strings with a logical type, and the
+/// `string` branches of unions that also have a `bytes` branch, are kept.
+pub fn as_bytes(json: &JsonValue) -> JsonValue {
+    match json {
+        JsonValue::String(kind) if kind == "string" => JsonValue::String("bytes".to_string()),
+        JsonValue::Array(variants) => {
+            let keep_strings = variants.iter().any(|variant| is_kind(variant, "bytes"));
+            JsonValue::Array(
+                variants
+                    .iter()
+                    .map(|variant| {
+                        if keep_strings && is_kind(variant, "string") {
+                            variant.clone()
+                        } else {
+                            as_bytes(variant)
+                        }
+                    })
+                    .collect(),
+            )
+        }
+        JsonValue::Object(object) if object.contains_key("logicalType") => json.clone(),
+        JsonValue::Object(object) => {
+            let mut object = object.clone();
+            for key in &["type", "items", "values"] {
+                if let Some(inner) = object.get_mut(*key) {
+                    *inner = as_bytes(inner);
+                }
+            }
+            if let Some(JsonValue::Array(fields)) = object.get_mut("fields") {
+                for field in fields.iter_mut().filter_map(JsonValue::as_object_mut) {
+                    if let Some(inner) = field.get_mut("type") {
+                        *inner = as_bytes(inner);
+                    }
+                }
+            }
+            JsonValue::Object(object)
+        }
+        _ => json.clone(),
+    }
+}
+
+/// Whether `json` is the primitive type `kind`, without logical type.
+fn is_kind(json: &JsonValue, kind: &str) -> bool {
+    match json {
+        JsonValue::String(name) => name == kind,
+        JsonValue::Object(object) => {
+            !object.contains_key("logicalType")
+                && object.get("type").and_then(JsonValue::as_str) == Some(kind)
+        }
+        _ => false,
+    }
+}
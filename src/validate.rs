@@ -0,0 +1,344 @@
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+use avro_rs::Schema;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::errors::ParseError;
+use crate::resolver::{named_type, Resolver, COMPLEX, PRIMITIVES};
+
+/// Parse a JSON schema, reporting problems with their location. The JSON
+/// value, with named type references inlined, is returned along with the
+/// schema.
+pub fn parse_str(input: &str) -> Result<(Schema, JsonValue), ParseError> {
+    parse_json(&serde_json::from_str(input)?)
+}
+
+/// Parse the JSON value of a schema, reporting problems with their location.
+/// The JSON value with named type references inlined is returned along with
+/// the schema.
+pub fn parse_json(json: &JsonValue) -> Result<(Schema, JsonValue), ParseError> {
+    validate(json)?;
+    let json = Resolver::new().resolve(json, "$", None)?;
+    let schema = Schema::parse(&json).map_err(|e| ParseError::new(e.as_fail().to_string()))?;
+    Ok((schema, json))
+}
+
+/// Structural checks run on the JSON of a schema before handing it to
+/// avro-rs, so problems can be reported with their location and a
+/// suggestion. avro-rs still has the final word on validity.
+pub fn validate(json: &JsonValue) -> Result<(), ParseError> {
+    Validator { names: Vec::new() }.check(json, "$", None)
+}
+
+struct Validator {
+    /// Full names of the named types defined so far.
+    names: Vec<String>,
+}
+
+impl Validator {
+    fn check(
+        &mut self,
+        json: &JsonValue,
+        path: &str,
+        namespace: Option<&str>,
+    ) -> Result<(), ParseError> {
+        match json {
+            JsonValue::String(name) => self.check_name(name, path, namespace),
+            JsonValue::Array(variants) => {
+                for (i, variant) in variants.iter().enumerate() {
+                    self.check(variant, &format!("{}[{}]", path, i), namespace)?;
+                }
+                Ok(())
+            }
+            JsonValue::Object(object) => self.check_object(object, path, namespace),
+            _ => Err(ParseError::new("Schema must be a JSON string, object or array").at(path)),
+        }
+    }
+
+    fn check_name(
+        &self,
+        name: &str,
+        path: &str,
+        namespace: Option<&str>,
+    ) -> Result<(), ParseError> {
+        let qualified = match namespace {
+            Some(namespace) if !name.contains('.') => format!("{}.{}", namespace, name),
+            _ => name.to_string(),
+        };
+        if PRIMITIVES.contains(&name)
+            || self.names.contains(&qualified)
+            || self.names.iter().any(|n| n == name)
+        {
+            return Ok(());
+        }
+
+        let candidates = PRIMITIVES
+            .iter()
+            .chain(COMPLEX.iter())
+            .map(|candidate| candidate.to_string())
+            .chain(self.names.iter().cloned());
+        Err(ParseError::new(format!("Unknown type '{}'", name))
+            .at(path)
+            .suggest(closest(name, candidates)))
+    }
+
+    fn check_object(
+        &mut self,
+        object: &Map<String, JsonValue>,
+        path: &str,
+        namespace: Option<&str>,
+    ) -> Result<(), ParseError> {
+        let kind = match object.get("type") {
+            Some(JsonValue::String(kind)) => kind.as_str(),
+            Some(inner) => return self.check(inner, &format!("{}.type", path), namespace),
+            None => return Err(ParseError::new("Missing 'type'").at(path)),
+        };
+
+        match kind {
+            "record" | "error" => {
+                let (fullname, namespace) = self.define(object, path, namespace)?;
+                let fields = match object.get("fields") {
+                    Some(JsonValue::Array(fields)) => fields,
+                    _ => {
+                        return Err(ParseError::new(format!(
+                            "Record '{}' has no 'fields' array",
+                            fullname
+                        ))
+                        .at(path))
+                    }
+                };
+                let mut seen = HashSet::new();
+                for (i, field) in fields.iter().enumerate() {
+                    let path = format!("{}.fields[{}]", path, i);
+                    let name = match field.get("name").and_then(JsonValue::as_str) {
+                        Some(name) => name,
+                        None => return Err(ParseError::new("Field has no 'name'").at(&path)),
+                    };
+                    if !seen.insert(name) {
+                        return Err(ParseError::new(format!(
+                            "Duplicate field '{}' in record '{}'",
+                            name, fullname
+                        ))
+                        .at(&format!("{}.name", path)));
+                    }
+                    match field.get("type") {
+                        Some(inner) => {
+                            self.check(inner, &format!("{}.type", path), namespace.as_deref())?
+                        }
+                        None => {
+                            return Err(ParseError::new(format!("Field '{}' has no 'type'", name))
+                                .at(&path))
+                        }
+                    }
+                }
+                Ok(())
+            }
+            "enum" => {
+                let (fullname, _) = self.define(object, path, namespace)?;
+                let symbols = match object.get("symbols") {
+                    Some(JsonValue::Array(symbols)) => symbols,
+                    _ => {
+                        return Err(ParseError::new(format!(
+                            "Enum '{}' has no 'symbols' array",
+                            fullname
+                        ))
+                        .at(path))
+                    }
+                };
+                let mut seen = HashSet::new();
+                for (i, symbol) in symbols.iter().enumerate() {
+                    let path = format!("{}.symbols[{}]", path, i);
+                    match symbol.as_str() {
+                        Some(symbol) if !seen.insert(symbol) => {
+                            return Err(ParseError::new(format!(
+                                "Duplicate symbol '{}' in enum '{}'",
+                                symbol, fullname
+                            ))
+                            .at(&path))
+                        }
+                        Some(_) => {}
+                        None => {
+                            return Err(ParseError::new("Enum symbols must be strings").at(&path))
+                        }
+                    }
+                }
+                Ok(())
+            }
+            "fixed" => {
+                let (fullname, _) = self.define(object, path, namespace)?;
+                match object.get("size") {
+                    Some(size) if size.is_u64() => Ok(()),
+                    _ => Err(ParseError::new(format!(
+                        "Fixed '{}' has no integer 'size'",
+                        fullname
+                    ))
+                    .at(&format!("{}.size", path))),
+                }
+            }
+            "array" => match object.get("items") {
+                Some(items) => self.check(items, &format!("{}.items", path), namespace),
+                None => Err(ParseError::new("Array has no 'items'").at(path)),
+            },
+            "map" => match object.get("values") {
+                Some(values) => self.check(values, &format!("{}.values", path), namespace),
+                None => Err(ParseError::new("Map has no 'values'").at(path)),
+            },
+            _ => self.check_name(kind, &format!("{}.type", path), namespace),
+        }
+    }
+
+    fn define(
+        &mut self,
+        object: &Map<String, JsonValue>,
+        path: &str,
+        namespace: Option<&str>,
+    ) -> Result<(String, Option<String>), ParseError> {
+        match named_type(object, namespace) {
+            Some((fullname, namespace)) => {
+                self.names.push(fullname.clone());
+                Ok((fullname, namespace))
+            }
+            None => Err(ParseError::new("Missing 'name'").at(path)),
+        }
+    }
+}
+
+/// The candidate closest to `name` by edit distance, if close enough to be a typo.
+pub fn closest<I: Iterator<Item = String>>(name: &str, candidates: I) -> Option<String> {
+    let threshold = (name.chars().count() / 3).max(1);
+    candidates
+        .map(|candidate| (distance(name, &candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two strings.
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == *cb { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Check that the default of every record field type-checks against the
+/// field schema (the first branch for unions), which avro-rs does not do.
+pub fn check_defaults(json: &JsonValue, path: &str) -> Result<(), ParseError> {
+    match json {
+        JsonValue::Array(variants) => {
+            for (i, variant) in variants.iter().enumerate() {
+                check_defaults(variant, &format!("{}[{}]", path, i))?;
+            }
+        }
+        JsonValue::Object(object) => {
+            for key in &["items", "values", "type"] {
+                if let Some(inner) = object.get(*key) {
+                    check_defaults(inner, &format!("{}.{}", path, key))?;
+                }
+            }
+            if let Some(JsonValue::Array(fields)) = object.get("fields") {
+                for (i, field) in fields.iter().enumerate() {
+                    if let Some(field) = field.as_object() {
+                        check_default(field, &format!("{}.fields[{}]", path, i))?;
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Check that the default of the record `field`, if any, type-checks
+/// against its schema, as well as the defaults nested in that schema.
+pub fn check_default(field: &Map<String, JsonValue>, path: &str) -> Result<(), ParseError> {
+    let schema = field.get("type").unwrap_or(&JsonValue::Null);
+    check_defaults(schema, &format!("{}.type", path))?;
+    match field.get("default") {
+        Some(default) if !matches_default(schema, default) => {
+            let name = field.get("name").and_then(JsonValue::as_str);
+            Err(ParseError::new(format!(
+                "Invalid default {} for field '{}'",
+                default,
+                name.unwrap_or_default()
+            ))
+            .at(&format!("{}.default", path)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether `default` is a valid default value for `schema`.
+fn matches_default(schema: &JsonValue, default: &JsonValue) -> bool {
+    match schema {
+        JsonValue::String(kind) => matches_kind(kind, default),
+        JsonValue::Array(variants) => variants
+            .first()
+            .is_some_and(|variant| matches_default(variant, default)),
+        JsonValue::Object(object) => match object.get("type") {
+            Some(JsonValue::String(kind)) => match kind.as_str() {
+                "record" | "error" => {
+                    let fields = object.get("fields").and_then(JsonValue::as_array);
+                    match (fields, default.as_object()) {
+                        (Some(fields), Some(values)) => fields.iter().all(|field| {
+                            let name = field.get("name").and_then(JsonValue::as_str);
+                            let schema = field.get("type").unwrap_or(&JsonValue::Null);
+                            match name.and_then(|name| values.get(name)) {
+                                Some(value) => matches_default(schema, value),
+                                None => field.get("default").is_some(),
+                            }
+                        }),
+                        _ => false,
+                    }
+                }
+                "enum" => {
+                    let symbols = object.get("symbols").and_then(JsonValue::as_array);
+                    symbols.is_some_and(|symbols| symbols.contains(default))
+                }
+                "fixed" => {
+                    let size = object.get("size").and_then(JsonValue::as_u64);
+                    let len = default.as_str().map(|value| value.chars().count() as u64);
+                    size.is_some() && size == len
+                }
+                "array" => match (object.get("items"), default.as_array()) {
+                    (Some(items), Some(values)) => {
+                        values.iter().all(|value| matches_default(items, value))
+                    }
+                    _ => false,
+                },
+                "map" => match (object.get("values"), default.as_object()) {
+                    (Some(schema), Some(values)) => {
+                        values.values().all(|value| matches_default(schema, value))
+                    }
+                    _ => false,
+                },
+                // Primitive types, possibly annotated with a logical type.
+                _ => matches_kind(kind, default),
+            },
+            Some(inner) => matches_default(inner, default),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn matches_kind(kind: &str, default: &JsonValue) -> bool {
+    match kind {
+        "null" => default.is_null(),
+        "boolean" => default.is_boolean(),
+        "int" => default.as_i64().is_some_and(|n| i32::try_from(n).is_ok()),
+        "long" => default.is_i64(),
+        "float" | "double" => default.is_number(),
+        "bytes" | "string" => default.is_string(),
+        // References to named types are not resolved here.
+        _ => true,
+    }
+}
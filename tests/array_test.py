@@ -0,0 +1,42 @@
+from array import array
+
+import pytest
+from pyo3avro_rs import Schema
+
+DOUBLES = '{"type": "array", "items": "double"}'
+LONGS = '{"type": "array", "items": "long"}'
+
+
+def test_write_from_buffer() -> None:
+    schema = Schema(DOUBLES)
+
+    assert schema.write(array("d", [1.5, 2.5])) == schema.write([1.5, 2.5])
+
+
+def test_write_mismatched_buffer_falls_back() -> None:
+    schema = Schema(LONGS)
+
+    assert schema.read(schema.write(array("i", [1, 2]))) == [1, 2]
+
+
+def test_read_array_mode() -> None:
+    schema = Schema(LONGS, arrays="array")
+
+    decoded = schema.read(schema.write([1, 2, 3]))
+
+    assert decoded == array("q", [1, 2, 3])
+
+
+def test_read_numpy_mode() -> None:
+    numpy = pytest.importorskip("numpy")
+    schema = Schema(DOUBLES, arrays="numpy")
+
+    decoded = schema.read(schema.write(numpy.arange(3, dtype=numpy.float64)))
+
+    assert decoded.dtype == numpy.float64
+    assert decoded.tolist() == [0.0, 1.0, 2.0]
+
+
+def test_unknown_arrays_mode() -> None:
+    with pytest.raises(ValueError):
+        Schema(LONGS, arrays="tuple")
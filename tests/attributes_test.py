@@ -0,0 +1,34 @@
+import json
+
+import pytest
+from pyo3avro_rs import Schema
+
+SCHEMA = """{
+    "type": "record", "name": "User", "owner": "identity-team",
+    "fields": [
+        {"name": "email", "type": "string", "sensitivity": "high"},
+        {"name": "orders", "type": {"type": "array", "items": {
+            "type": "record", "name": "Order", "fields": [
+                {"name": "total", "type": "double", "sqlType": "NUMERIC(10, 2)"}
+            ]
+        }}}
+    ]
+}"""
+
+
+def test_to_json_preserves_custom_attributes() -> None:
+    assert json.loads(Schema(SCHEMA).to_json()) == json.loads(SCHEMA)
+
+
+def test_attributes() -> None:
+    schema = Schema(SCHEMA)
+
+    assert schema.attributes() == {"owner": "identity-team"}
+    assert schema.attributes("email") == {"sensitivity": "high"}
+    assert schema.attributes("orders.total") == {"sqlType": "NUMERIC(10, 2)"}
+    assert schema.attributes("orders") == {}
+
+
+def test_unknown_field() -> None:
+    with pytest.raises(ValueError, match="Unknown field path: phone"):
+        Schema(SCHEMA).attributes("phone")
@@ -0,0 +1,17 @@
+import pytest
+from pyo3avro_rs import Schema, bench
+
+SCHEMA = Schema('{"type": "record", "name": "R", "fields": [{"name": "id", "type": "long"}]}')
+
+
+def test_bench() -> None:
+    result = bench(SCHEMA, [{"id": 1}, {"id": 300}], iterations=10)
+
+    assert result.records == 20
+    assert result.bytes == 3
+    assert min(result.encode, result.decode, result.python_encode, result.python_decode) > 0
+
+
+def test_bench_without_samples() -> None:
+    with pytest.raises(ValueError):
+        bench(SCHEMA, [])
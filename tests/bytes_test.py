@@ -0,0 +1,60 @@
+import pytest
+from pyo3avro_rs import Schema
+
+BYTES = '"bytes"'
+
+
+def test_read_returns_bytes() -> None:
+    schema = Schema(BYTES)
+
+    assert schema.read(schema.write(b"\x00\xff")) == b"\x00\xff"
+
+
+def test_write_str_as_utf8() -> None:
+    schema = Schema(BYTES)
+
+    assert schema.read(schema.write("é")) == "é".encode("utf-8")
+
+
+def test_write_str_with_encoding() -> None:
+    schema = Schema(BYTES, bytes_encoding="latin-1")
+
+    assert schema.read(schema.write("é")) == b"\xe9"
+
+
+def test_write_str_rejected() -> None:
+    schema = Schema(BYTES, bytes_encoding=None)
+
+    with pytest.raises(TypeError):
+        schema.write("text")
+
+
+def test_write_str_in_union() -> None:
+    with_string = Schema('["null", "bytes", "string"]')
+    without_string = Schema('["null", "bytes"]')
+
+    assert with_string.read(with_string.write("hello")) == "hello"
+    assert with_string.read(with_string.write(b"hello")) == b"hello"
+    assert without_string.read(without_string.write("hello")) == b"hello"
+
+
+def test_decode_bytes_as_str() -> None:
+    schema = Schema(
+        '{"type": "record", "name": "R", "fields": [{"name": "text", "type": "bytes"}]}',
+        decode_bytes="utf-8",
+    )
+
+    assert schema.read(schema.write({"text": b"caf\xc3\xa9"})) == {"text": "café"}
+    with pytest.raises(UnicodeDecodeError):
+        schema.read(schema.write({"text": b"\xff"}))
+
+
+def test_write_buffer() -> None:
+    schema = Schema('{"type": "record", "name": "R", "fields": [{"name": "data", "type": "bytes"}]}')
+    datum = {"data": b"\x01" * 4096}
+
+    view = memoryview(schema.write_buffer(datum))
+
+    assert view.readonly
+    assert view == schema.write(datum)
+    assert schema.read(bytes(view)) == datum
@@ -0,0 +1,62 @@
+import datetime
+
+import pytest
+from pyo3avro_rs import Schema
+
+SCHEMA = """{
+    "type": "record", "name": "Event", "fields": [
+        {"name": "user_id", "type": ["null", "string"]},
+        {"name": "created", "type": "long"},
+        {"name": "score", "type": "string"}
+    ]
+}"""
+
+
+def test_coercions() -> None:
+    schema = Schema(SCHEMA)
+    schema.register_coercions(
+        {"string->int": ["user_id"], "long->datetime(ms)": ["created"], "string->float": ["score"]}
+    )
+
+    payload = schema.write({"user_id": " 42", "created": 1_600_000_000_123, "score": "0.5"})
+
+    assert schema.read(payload) == {
+        "user_id": 42,
+        "created": datetime.datetime(2020, 9, 13, 12, 26, 40, 123000, tzinfo=datetime.timezone.utc),
+        "score": 0.5,
+    }
+    assert schema.read(schema.write({"user_id": None, "created": 0, "score": "1"}))["user_id"] is None
+
+
+def test_invalid_value() -> None:
+    schema = Schema(SCHEMA)
+    schema.register_coercions({"string->int": ["score"]})
+
+    with pytest.raises(ValueError, match="Cannot coerce 'high' of field score to int"):
+        schema.read(schema.write({"user_id": None, "created": 0, "score": "high"}))
+
+
+def test_datetime_overflow() -> None:
+    schema = Schema(SCHEMA)
+    schema.register_coercions({"long->datetime(s)": ["created"]})
+
+    with pytest.raises(ValueError, match="Cannot coerce 9223372036854775807 of field created to datetime"):
+        schema.read(schema.write({"user_id": None, "created": 2**63 - 1, "score": "1"}))
+
+
+def test_coercions_with_decode_errors() -> None:
+    schema = Schema(SCHEMA, decode_errors="replace")
+    schema.register_coercions({"string->int": ["user_id"], "string->float": ["score"]})
+
+    assert schema.read(schema.write({"user_id": "42", "created": 0, "score": "0.5"})) == {
+        "user_id": 42,
+        "created": 0,
+        "score": 0.5,
+    }
+
+
+def test_unknown_coercion() -> None:
+    with pytest.raises(ValueError, match="Unknown coercion: string->bool"):
+        Schema(SCHEMA).register_coercions({"string->bool": ["score"]})
+    with pytest.raises(ValueError, match="Unknown field path: id"):
+        Schema(SCHEMA).register_coercions({"string->int": ["id"]})
@@ -0,0 +1,61 @@
+import pytest
+from pyo3avro_rs import Schema, check_file_schema
+
+V1 = '{"type": "record", "name": "Order", "fields": [{"name": "id", "type": "long"}]}'
+V2 = (
+    '{"type": "record", "name": "Order", "fields": ['
+    '{"name": "id", "type": "long"}, {"name": "note", "type": "string"}]}'
+)
+HEADER = Schema('{"type": "map", "values": "bytes"}')
+
+
+def container_file(tmp_path, schema: str) -> str:
+    path = tmp_path / "orders.avro"
+    metadata = HEADER.write({"avro.schema": schema.encode(), "avro.codec": b"null"})
+    path.write_bytes(b"Obj\x01" + metadata + bytes(16))
+    return str(path)
+
+
+def test_same_schema(tmp_path) -> None:
+    report = check_file_schema(container_file(tmp_path, V1), Schema(V1))
+
+    assert (report.mode, report.compatible, report.same_schema) == ("BACKWARD", True, True)
+    assert report.writer_schema == '{"name":"Order","type":"record","fields":[{"name":"id","type":"long"}]}'
+
+
+def test_modes(tmp_path) -> None:
+    path = container_file(tmp_path, V2)
+
+    assert check_file_schema(path, Schema(V1)).compatible
+    assert not check_file_schema(path, Schema(V1), mode="FORWARD").compatible
+    assert not check_file_schema(path, Schema(V1), mode="FULL").compatible
+
+
+def test_not_a_container_file(tmp_path) -> None:
+    path = tmp_path / "orders.avro"
+    path.write_bytes(b"nope")
+
+    with pytest.raises(ValueError):
+        check_file_schema(str(path), Schema(V1))
+    with pytest.raises(ValueError, match="Unknown compatibility mode: NONE"):
+        check_file_schema(container_file(tmp_path, V1), Schema(V1), mode="NONE")
+
+
+def test_unknown_mode_without_file(tmp_path) -> None:
+    with pytest.raises(ValueError, match="Unknown compatibility mode: NONE"):
+        check_file_schema(str(tmp_path / "missing.avro"), Schema(V1), mode="NONE")
+
+
+def test_canonical_form_qualifies_names(tmp_path) -> None:
+    schema = (
+        '{"type": "record", "name": "Order", "namespace": "com.acme", "fields": ['
+        '{"name": "item", "type": {"type": "record", "name": "Item", "fields": ['
+        '{"name": "sku", "type": "string", "doc": "stock keeping unit"}]}}]}'
+    )
+    report = check_file_schema(container_file(tmp_path, schema), Schema(schema))
+
+    assert report.same_schema
+    assert report.writer_schema == (
+        '{"name":"com.acme.Order","type":"record","fields":[{"name":"item","type":'
+        '{"name":"com.acme.Item","type":"record","fields":[{"name":"sku","type":"string"}]}}]}'
+    )
@@ -0,0 +1,110 @@
+import pytest
+from pyo3avro_rs import Schema, SchemaParseError
+
+SCHEMA = Schema(
+    """{
+    "type": "record", "name": "User", "fields": [
+        {"name": "id", "type": "long"},
+        {"name": "address", "type": {"type": "record", "name": "Address", "fields": [
+            {"name": "city", "type": "string"}
+        ]}}
+    ]
+}"""
+)
+
+
+def test_with_field() -> None:
+    schema = SCHEMA.with_field("address.zip", '["null", "string"]', default=None)
+
+    datum = {"id": 1, "address": {"city": "Paris", "zip": "75001"}}
+    assert schema.read(schema.write(datum)) == datum
+
+
+def test_with_field_requires_default() -> None:
+    with pytest.raises(ValueError, match="Field email needs a default"):
+        SCHEMA.with_field("email", '"string"')
+
+
+def test_with_field_collision() -> None:
+    with pytest.raises(ValueError, match="Field id already exists"):
+        SCHEMA.with_field("id", '"string"', default="")
+
+
+def test_without_field() -> None:
+    schema = SCHEMA.without_field("address.city").without_field("address")
+
+    assert schema.read(schema.write({"id": 1})) == {"id": 1}
+    with pytest.raises(ValueError, match="Unknown field path: name"):
+        SCHEMA.without_field("name")
+
+
+SHARED = Schema(
+    """{
+    "type": "record", "name": "Person", "fields": [
+        {"name": "home", "type": {"type": "record", "name": "Addr", "fields": [
+            {"name": "city", "type": "string"}
+        ]}},
+        {"name": "work", "type": "Addr"}
+    ]
+}"""
+)
+
+
+def test_edit_shared_type() -> None:
+    added = SHARED.with_field("home.zip", '"string"', default="")
+    removed = SHARED.without_field("work.city")
+
+    datum = {"home": {"city": "Paris", "zip": "75001"}, "work": {"city": "Lyon", "zip": "69001"}}
+    assert Schema(added.to_json()).read(added.write(datum)) == datum
+    assert Schema(removed.to_json()).read(removed.write({"home": {}, "work": {}})) == {"home": {}, "work": {}}
+
+
+def test_merge() -> None:
+    other = Schema(
+        """{"type": "record", "name": "Extra", "fields": [
+            {"name": "id", "type": "long"}, {"name": "age", "type": "int", "default": 0}
+        ]}"""
+    )
+
+    schema = SCHEMA.merge(other)
+
+    datum = {"id": 1, "address": {"city": "Paris"}, "age": 3}
+    assert schema.read(schema.write(datum)) == datum
+
+    clash = Schema('{"type": "record", "name": "Clash", "fields": [{"name": "id", "type": "string"}]}')
+    with pytest.raises(ValueError, match="Field id is defined differently in both schemas"):
+        SCHEMA.merge(clash)
+
+
+def test_merge_named_type_collision() -> None:
+    first = Schema(
+        """{"type": "record", "name": "A", "fields": [
+            {"name": "x", "type": {"type": "record", "name": "Addr", "fields": [{"name": "city", "type": "string"}]}}
+        ]}"""
+    )
+    second = Schema(
+        """{"type": "record", "name": "B", "fields": [
+            {"name": "y", "type": {"type": "record", "name": "Addr", "fields": [{"name": "zip", "type": "string"}]},
+             "default": {"zip": ""}}
+        ]}"""
+    )
+
+    with pytest.raises(SchemaParseError, match="Named type Addr is defined differently in both schemas"):
+        first.merge(second)
+
+
+def test_merge_requires_defaults() -> None:
+    missing = Schema('{"type": "record", "name": "Extra", "fields": [{"name": "note", "type": "string"}]}')
+    invalid = Schema(
+        '{"type": "record", "name": "Extra", "fields": [{"name": "note", "type": "string", "default": 0}]}'
+    )
+
+    with pytest.raises(SchemaParseError, match="Field note needs a default"):
+        SCHEMA.merge(missing)
+    with pytest.raises(SchemaParseError, match="Invalid default 0 for field 'note'"):
+        SCHEMA.merge(invalid)
+
+
+def test_with_field_invalid_default() -> None:
+    with pytest.raises(SchemaParseError, match="Invalid default 0 for field 'email'"):
+        SCHEMA.with_field("email", '"string"', default=0)
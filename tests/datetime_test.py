@@ -0,0 +1,45 @@
+import datetime
+
+import pytest
+from pyo3avro_rs import Schema
+
+TIMESTAMP = Schema('{"type": "long", "logicalType": "timestamp-micros"}')
+DATE = Schema('{"type": "int", "logicalType": "date"}')
+UTC = datetime.timezone.utc
+EXPECTED = datetime.datetime(2021, 3, 4, 5, 6, 7, 8, tzinfo=UTC)
+
+
+class FakeDatetime(datetime.datetime):
+    pass
+
+
+def test_datetime_subclass() -> None:
+    assert TIMESTAMP.read(TIMESTAMP.write(FakeDatetime(2021, 3, 4, 5, 6, 7, 8))) == EXPECTED
+
+
+def test_aware_datetime() -> None:
+    tz = datetime.timezone(datetime.timedelta(hours=2))
+    datum = datetime.datetime(2021, 3, 4, 7, 6, 7, 8, tzinfo=tz)
+
+    assert TIMESTAMP.read(TIMESTAMP.write(datum)) == EXPECTED
+
+
+def test_numpy_datetime64() -> None:
+    numpy = pytest.importorskip("numpy")
+
+    assert TIMESTAMP.read(TIMESTAMP.write(numpy.datetime64("2021-03-04T05:06:07.000008"))) == EXPECTED
+    assert DATE.read(DATE.write(numpy.datetime64("2021-03-04"))) == datetime.date(2021, 3, 4)
+
+
+def test_pandas_timestamp() -> None:
+    pandas = pytest.importorskip("pandas")
+
+    assert TIMESTAMP.read(TIMESTAMP.write(pandas.Timestamp("2021-03-04T05:06:07.000008Z"))) == EXPECTED
+    assert DATE.read(DATE.write(pandas.Timestamp("2021-03-04"))) == datetime.date(2021, 3, 4)
+
+
+def test_timestamp_millis_out_of_range() -> None:
+    payload = Schema('"long"').write(2**63 - 1)
+
+    with pytest.raises(ValueError, match="timestamp-millis 9223372036854775807 is out of range"):
+        Schema('{"type": "long", "logicalType": "timestamp-millis"}').read(payload)
@@ -0,0 +1,40 @@
+import decimal
+
+import pytest
+from pyo3avro_rs import Schema
+
+BYTES = Schema('{"type": "bytes", "logicalType": "decimal", "precision": 50, "scale": 2}')
+FIXED = Schema(
+    '{"type": {"type": "fixed", "name": "Amount", "size": 4}, "logicalType": "decimal", "precision": 9, "scale": 2}'
+)
+
+
+@pytest.mark.parametrize(
+    "datum, expected",
+    [
+        (decimal.Decimal("-1.23"), decimal.Decimal("-1.23")),
+        (decimal.Decimal("12.5"), decimal.Decimal("12.50")),
+        (decimal.Decimal("0"), decimal.Decimal("0.00")),
+        (-123, decimal.Decimal("-1.23")),
+        (10**47 + 1, decimal.Decimal(10**47 + 1).scaleb(-2)),
+    ],
+)
+def test_bytes_decimal(datum: object, expected: decimal.Decimal) -> None:
+    assert BYTES.read(BYTES.write(datum)) == expected
+
+
+def test_minimal_encoding() -> None:
+    assert BYTES.write(-123) == b"\x02\x85"
+    assert BYTES.write(128) == b"\x04\x00\x80"
+
+
+def test_fixed_decimal() -> None:
+    assert FIXED.write(decimal.Decimal("-1.23")) == b"\xff\xff\xff\x85"
+    assert FIXED.read(FIXED.write(1234567)) == decimal.Decimal("12345.67")
+
+
+def test_out_of_range() -> None:
+    with pytest.raises(ValueError, match="more than 2 decimal places"):
+        BYTES.write(decimal.Decimal("1.234"))
+    with pytest.raises(ValueError, match="does not fit a decimal of precision 9"):
+        FIXED.write(10**9)
@@ -0,0 +1,88 @@
+import struct
+
+import pytest
+from pyo3avro_rs import Decoder, Schema
+
+RECORD = Schema(
+    '{"type": "record", "name": "Order", "fields": [{"name": "id", "type": "long"}]}'
+)
+
+
+def test_fingerprint() -> None:
+    # Known CRC-64-AVRO value for the "null" schema.
+    assert Schema('"null"').fingerprint == struct.pack("<q", 7195948357588979594)
+
+
+def rabin(data: bytes) -> int:
+    empty = 0xC15D213AA4D7A795
+    table = []
+    for i in range(256):
+        fp = i
+        for _ in range(8):
+            fp = (fp >> 1) ^ (empty & -(fp & 1))
+        table.append(fp)
+    fp = empty
+    for byte in data:
+        fp = (fp >> 8) ^ table[(fp ^ byte) & 0xFF]
+    return fp
+
+
+def test_fingerprint_of_canonical_form() -> None:
+    schema = Schema(
+        """{"type": "record", "name": "R", "namespace": "ns", "doc": "d", "fields": [
+            {"name": "e", "type": {"type": "enum", "name": "E", "symbols": ["A", "B"]}, "default": "A"},
+            {"name": "f", "type": "E"},
+            {"name": "t", "type": {"type": "long", "logicalType": "timestamp-millis"}}
+        ]}"""
+    )
+    canonical = (
+        '{"name":"ns.R","type":"record","fields":['
+        '{"name":"e","type":{"name":"ns.E","type":"enum","symbols":["A","B"]}},'
+        '{"name":"f","type":"ns.E"},'
+        '{"name":"t","type":"long"}]}'
+    )
+
+    assert schema.fingerprint == struct.pack("<Q", rabin(canonical.encode()))
+
+
+def test_read_single_object() -> None:
+    calls = []
+
+    def resolve(fingerprint):
+        calls.append(fingerprint)
+        return RECORD
+
+    decoder = Decoder(resolve)
+    message = b"\xc3\x01" + RECORD.fingerprint + RECORD.write({"id": 42})
+
+    assert decoder.read(message) == {"id": 42}
+    assert decoder.read(message) == {"id": 42}
+    assert calls == [RECORD.fingerprint]
+
+
+def test_read_registry_framed() -> None:
+    decoder = Decoder({7: RECORD}.get)
+    message = b"\x00" + struct.pack(">I", 7) + RECORD.write({"id": 1})
+
+    assert decoder.read(message) == {"id": 1}
+
+
+def test_read_with_decode_errors() -> None:
+    schema = Schema('"string"', decode_errors="replace")
+    decoder = Decoder(lambda reference: schema)
+
+    assert decoder.read(b"\xc3\x01" + schema.fingerprint + b"\x0aab\xffcd") == "ab�cd"
+
+
+def test_unresolved_schema() -> None:
+    decoder = Decoder(lambda reference: None)
+
+    with pytest.raises(ValueError):
+        decoder.read(b"\x00\x00\x00\x00\x01\x02")
+
+
+def test_unframed_message() -> None:
+    decoder = Decoder(lambda reference: RECORD)
+
+    with pytest.raises(ValueError):
+        decoder.read(RECORD.write({"id": 1}))
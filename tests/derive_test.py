@@ -0,0 +1,60 @@
+import enum
+from dataclasses import dataclass, field
+from datetime import date, datetime, timezone
+from typing import Dict, List, Optional, TypedDict
+
+import pytest
+from pyo3avro_rs import schema_from_type
+
+
+class Status(enum.Enum):
+    ACTIVE = 1
+    BLOCKED = 2
+
+
+@dataclass
+class Address:
+    city: str
+
+
+@dataclass
+class Account:
+    id: int
+    created: datetime
+    birthday: date
+    status: Status
+    address: Address
+    tags: List[str] = field(default_factory=list)
+    limits: Dict[str, float] = field(default_factory=dict)
+    nickname: Optional[str] = None
+
+
+class Point(TypedDict):
+    x: float
+    y: float
+
+
+def test_dataclass_round_trip() -> None:
+    schema = schema_from_type(Account)
+    account = Account(
+        id=1,
+        created=datetime(2020, 5, 17, 12, 30, 1, 123456, tzinfo=timezone.utc),
+        birthday=date(1990, 2, 3),
+        status=Status.BLOCKED,
+        address=Address(city="Paris"),
+        tags=["a"],
+        limits={"daily": 10.0},
+    )
+
+    assert schema.read(schema.write(account)) == account
+
+
+def test_typed_dict() -> None:
+    schema = schema_from_type(Point)
+
+    assert schema.read(schema.write({"x": 1.0, "y": 2.0})) == {"x": 1.0, "y": 2.0}
+
+
+def test_unsupported_class() -> None:
+    with pytest.raises(TypeError, match="Expected a dataclass, TypedDict or pydantic model"):
+        schema_from_type(int)
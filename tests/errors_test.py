@@ -0,0 +1,90 @@
+import pytest
+from pyo3avro_rs import Schema, SchemaParseError
+
+
+def test_is_value_error() -> None:
+    assert issubclass(SchemaParseError, ValueError)
+
+
+def test_syntax_error() -> None:
+    with pytest.raises(SchemaParseError) as error:
+        Schema('{"type": "record",\n "name": }')
+
+    assert (error.value.line, error.value.column) == (2, 10)
+    assert error.value.path is None
+
+
+def test_unknown_type() -> None:
+    with pytest.raises(SchemaParseError) as error:
+        Schema(
+            '{"type": "record", "name": "User", "fields": ['
+            '{"name": "id", "type": "long"}, {"name": "email", "type": "strng"}]}'
+        )
+
+    assert error.value.path == "$.fields[1].type"
+    assert error.value.suggestion == "string"
+    assert "did you mean 'string'?" in str(error.value)
+
+
+def test_duplicate_field() -> None:
+    with pytest.raises(SchemaParseError) as error:
+        Schema(
+            '{"type": "record", "name": "User", "fields": ['
+            '{"name": "id", "type": "long"}, {"name": "id", "type": "string"}]}'
+        )
+
+    assert error.value.path == "$.fields[1].name"
+    assert "Duplicate field 'id'" in str(error.value)
+
+
+def test_nested_location() -> None:
+    with pytest.raises(SchemaParseError) as error:
+        Schema('["null", {"type": "map", "values": {"type": "enum", "name": "E", "symbols": ["A", "A"]}}]')
+
+    assert error.value.path == "$[1].values.symbols[1]"
+
+
+def test_file_location(tmp_path) -> None:
+    path = tmp_path / "user.avsc"
+    path.write_text('{"type": "array", "items": "Usr"}')
+
+    with pytest.raises(SchemaParseError) as error:
+        Schema.from_file(str(path))
+
+    assert error.value.file == str(path)
+    assert error.value.path == "$.items"
+
+
+def test_invalid_default() -> None:
+    schema = (
+        '{"type": "record", "name": "User", "fields": ['
+        '{"name": "id", "type": "long"}, {"name": "email", "type": ["string", "null"], "default": null}]}'
+    )
+    Schema(schema)
+
+    with pytest.raises(SchemaParseError) as error:
+        Schema(schema, check_defaults=True)
+
+    assert error.value.path == "$.fields[1].default"
+    assert "Invalid default null for field 'email'" in str(error.value)
+
+
+def test_nested_invalid_default() -> None:
+    with pytest.raises(SchemaParseError) as error:
+        Schema(
+            '{"type": "array", "items": {"type": "record", "name": "R", "fields": ['
+            '{"name": "n", "type": "int", "default": 4294967296}]}}',
+            check_defaults=True,
+        )
+
+    assert error.value.path == "$.items.fields[0].default"
+
+
+def test_valid_defaults() -> None:
+    Schema(
+        '{"type": "record", "name": "User", "fields": ['
+        '{"name": "email", "type": ["null", "string"], "default": null},'
+        '{"name": "tags", "type": {"type": "array", "items": "string"}, "default": ["a"]},'
+        '{"name": "kind", "type": {"type": "enum", "name": "Kind", "symbols": ["A", "B"]}, "default": "B"}]}',
+        check_defaults=True,
+    )
@@ -0,0 +1,117 @@
+import json
+import os
+import sys
+
+import pytest
+from pyo3avro_rs import Schema
+
+ITEM = {
+    "type": "record",
+    "name": "Item",
+    "namespace": "com.acme",
+    "fields": [{"name": "sku", "type": "string"}],
+}
+ORDER = {
+    "type": "record",
+    "name": "Order",
+    "namespace": "com.acme",
+    "fields": [
+        {"name": "items", "type": {"type": "array", "items": "Item"}},
+        {"name": "gift", "type": ["null", "com.acme.Item"]},
+    ],
+}
+
+
+def write(path, schema):
+    path.write_text(json.dumps(schema))
+    return str(path)
+
+
+def test_from_file_resolves_siblings(tmp_path) -> None:
+    write(tmp_path / "item.avsc", ITEM)
+    schema = Schema.from_file(write(tmp_path / "order.avsc", ORDER))
+    order = {"items": [{"sku": "a"}], "gift": {"sku": "b"}}
+
+    assert schema.read(schema.write(order)) == order
+
+
+def test_from_file_search_paths(tmp_path) -> None:
+    (tmp_path / "common").mkdir()
+    (tmp_path / "orders").mkdir()
+    write(tmp_path / "common" / "item.avsc", ITEM)
+    path = write(tmp_path / "orders" / "order.avsc", ORDER)
+
+    with pytest.raises(ValueError, match="Unknown type 'Item'"):
+        Schema.from_file(path)
+    assert Schema.from_file(path, search_paths=[str(tmp_path / "common")])
+
+
+def test_from_files(tmp_path) -> None:
+    (tmp_path / "a").mkdir()
+    (tmp_path / "b").mkdir()
+    item, order = Schema.from_files(
+        [write(tmp_path / "a" / "item.avsc", ITEM), write(tmp_path / "b" / "order.avsc", ORDER)]
+    )
+
+    assert item.read(item.write({"sku": "a"})) == {"sku": "a"}
+    assert order.read(order.write({"items": [], "gift": None})) == {"items": [], "gift": None}
+
+
+def test_syntax_error_location(tmp_path) -> None:
+    path = tmp_path / "broken.avsc"
+    path.write_text('{\n  "type": "record",\n  "name": \n}')
+
+    with pytest.raises(ValueError, match="broken.avsc:4:1"):
+        Schema.from_file(str(path))
+
+
+def test_unrelated_broken_file(tmp_path) -> None:
+    (tmp_path / "broken.avsc").write_text("{")
+    write(tmp_path / "item.avsc", ITEM)
+    schema = Schema.from_file(write(tmp_path / "order.avsc", ORDER))
+
+    assert schema.read(schema.write({"items": [], "gift": None})) == {"items": [], "gift": None}
+
+
+def test_broken_file_reported_for_unknown_type(tmp_path) -> None:
+    (tmp_path / "item.avsc").write_text("{")
+
+    with pytest.raises(ValueError, match="item.avsc:1:1"):
+        Schema.from_file(write(tmp_path / "order.avsc", ORDER))
+
+
+def test_conflicting_definitions(tmp_path) -> None:
+    write(tmp_path / "a.avsc", ITEM)
+    write(tmp_path / "b.avsc", {**ITEM, "fields": [{"name": "code", "type": "int"}]})
+
+    with pytest.raises(ValueError, match="com.acme.Item is defined differently in .*a.avsc and .*b.avsc"):
+        Schema.from_file(write(tmp_path / "order.avsc", ORDER))
+
+
+def test_recursive_reference(tmp_path) -> None:
+    node = {
+        "type": "record",
+        "name": "Node",
+        "fields": [{"name": "next", "type": ["null", "Node"]}],
+    }
+
+    with pytest.raises(ValueError, match="Recursive reference to Node"):
+        Schema.from_file(write(tmp_path / "node.avsc", node))
+
+
+def test_path_like(tmp_path) -> None:
+    write(tmp_path / "item.avsc", ITEM)
+    (tmp_path / "order.avsc").write_text(json.dumps(ORDER))
+
+    assert Schema.from_file(tmp_path / "order.avsc", search_paths=[tmp_path])
+
+
+@pytest.mark.skipif(sys.platform == "win32", reason="bytes paths are decoded on Windows")
+def test_non_utf8_path(tmp_path) -> None:
+    directory = os.fsencode(tmp_path) + b"/caf\xe9"
+    os.mkdir(directory)
+    with open(directory + b"/item.avsc", "w") as file:
+        json.dump(ITEM, file)
+
+    assert Schema.from_file(directory + b"/item.avsc")
+    assert Schema.from_file(os.fsdecode(directory + b"/item.avsc"))
@@ -0,0 +1,64 @@
+import hashlib
+
+import pytest
+from pyo3avro_rs import Schema
+
+SCHEMA = """{
+    "type": "record", "name": "Event", "fields": [
+        {"name": "user", "type": {"type": "record", "name": "User", "fields": [
+            {"name": "email", "type": "string"},
+            {"name": "ssn", "type": "string"}
+        ]}},
+        {"name": "contacts", "type": {"type": "array", "items": {
+            "type": "record", "name": "Contact", "fields": [{"name": "email", "type": "string"}]
+        }}}
+    ]
+}"""
+
+
+def sha256(value: str) -> str:
+    return hashlib.sha256(value.encode()).hexdigest()
+
+
+def test_encode_hooks() -> None:
+    schema = Schema(SCHEMA)
+    schema.register_hooks(encode={"user.email": lambda _: "***", "user.ssn": sha256})
+    event = {"user": {"email": "a@b.c", "ssn": "123"}, "contacts": [{"email": "d@e.f"}]}
+
+    assert schema.read(schema.write(event)) == {
+        "user": {"email": "***", "ssn": sha256("123")},
+        "contacts": [{"email": "d@e.f"}],
+    }
+
+
+def test_decode_hooks_through_arrays() -> None:
+    schema = Schema(SCHEMA)
+    schema.register_hooks(decode={"contacts.email": str.upper})
+    event = {"user": {"email": "a@b.c", "ssn": "123"}, "contacts": [{"email": "d@e.f"}]}
+
+    assert schema.read(schema.write(event)) == {
+        "user": {"email": "a@b.c", "ssn": "123"},
+        "contacts": [{"email": "D@E.F"}],
+    }
+
+
+def test_unknown_path() -> None:
+    schema = Schema(SCHEMA)
+    with pytest.raises(ValueError, match="Unknown field path: user.phone"):
+        schema.register_hooks(encode={"user.phone": str})
+
+
+def test_normalizer() -> None:
+    schema = Schema(SCHEMA)
+
+    def normalize(event: dict) -> dict:
+        return {**event, "user": {**event["user"], "email": event["user"]["email"].strip().lower()}}
+
+    schema.register_normalizer(normalize)
+    schema.register_hooks(encode={"user.email": lambda email: email.replace("@", " at ")})
+    event = {"user": {"email": " A@B.C ", "ssn": "123"}, "contacts": []}
+
+    assert schema.read(schema.write(event))["user"]["email"] == "a at b.c"
+
+    schema.register_normalizer(None)
+    assert schema.read(schema.write(event))["user"]["email"] == " A at B.C "
@@ -0,0 +1,48 @@
+import pytest
+from pyo3avro_rs import Schema, lint_schema
+
+SCHEMA = Schema(
+    """{
+    "type": "record", "name": "Payment", "fields": [
+        {"name": "paymentId", "type": "long", "doc": "Identifier", "default": 0},
+        {"name": "amount", "type": "float"},
+        {"name": "fee", "type": {"type": "bytes", "logicalType": "decimal", "precision": 6, "scale": 2}}
+    ]
+}"""
+)
+
+
+def test_all_rules() -> None:
+    findings = [(finding.rule, finding.path) for finding in lint_schema(SCHEMA)]
+
+    assert findings == [
+        ("namespaced-types", "$"),
+        ("type-docs", "$"),
+        ("snake-case-fields", "$.fields[0]"),
+        ("field-docs", "$.fields[1]"),
+        ("field-defaults", "$.fields[1]"),
+        ("forbidden-types", "$.fields[1].type"),
+        ("field-docs", "$.fields[2]"),
+        ("field-defaults", "$.fields[2]"),
+    ]
+
+
+def test_selected_rules() -> None:
+    findings = lint_schema(SCHEMA, rules=["forbidden-types"], forbidden_types=["long", "float"])
+
+    assert [finding.path for finding in findings] == ["$.fields[0].type", "$.fields[1].type"]
+    assert findings[0].message == "Type 'long' is forbidden"
+
+
+def test_clean_schema() -> None:
+    schema = Schema(
+        '{"type": "record", "name": "Event", "namespace": "com.acme", "doc": "An event", "fields": ['
+        '{"name": "event_id", "type": "long", "doc": "Identifier", "default": 0}]}'
+    )
+
+    assert lint_schema(schema) == []
+
+
+def test_unknown_rule() -> None:
+    with pytest.raises(ValueError, match="Unknown lint rule: camel-case"):
+        lint_schema(SCHEMA, rules=["camel-case"])
@@ -0,0 +1,14 @@
+import logging
+
+import pytest
+from pyo3avro_rs import Schema
+
+
+def test_logs_decode_failure(caplog: pytest.LogCaptureFixture) -> None:
+    schema = Schema('"string"')
+
+    with caplog.at_level(logging.DEBUG, logger="pyo3avro_rs"), pytest.raises(ValueError):
+        schema.read(b"\x08ab")
+
+    messages = [record.message for record in caplog.records if record.name == "pyo3avro_rs"]
+    assert any("Failed to decode 3 bytes" in message for message in messages)
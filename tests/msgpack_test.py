@@ -0,0 +1,32 @@
+import pytest
+from pyo3avro_rs import Schema
+
+SCHEMA = """{
+    "type": "record", "name": "Reading", "fields": [
+        {"name": "id", "type": "long"},
+        {"name": "ok", "type": "boolean"},
+        {"name": "tag", "type": ["null", "string"]},
+        {"name": "values", "type": {"type": "array", "items": "int"}}
+    ]
+}"""
+
+
+def test_read_to_msgpack() -> None:
+    schema = Schema(SCHEMA)
+    datum = schema.write({"id": 300, "ok": True, "tag": "a", "values": [1, -1, -200]})
+
+    assert schema.read_to_msgpack(datum) == (
+        b"\x84"
+        + b"\xa2id\xd1\x01\x2c"
+        + b"\xa2ok\xc3"
+        + b"\xa3tag\xa1a"
+        + b"\xa6values\x93\x01\xff\xd1\xff\x38"
+    )
+
+
+def test_matches_read() -> None:
+    msgpack = pytest.importorskip("msgpack")
+    schema = Schema(SCHEMA)
+    value = {"id": 1 << 40, "ok": False, "tag": None, "values": list(range(20))}
+
+    assert msgpack.unpackb(schema.read_to_msgpack(schema.write(value))) == value
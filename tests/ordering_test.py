@@ -0,0 +1,19 @@
+from pyo3avro_rs import Schema
+
+SCHEMA = """{
+    "type": "record", "name": "Golden", "fields": [
+        {"name": "b", "type": "int"},
+        {"name": "a", "type": {"type": "map", "values": "int"}}
+    ]
+}"""
+
+
+def test_sorted_maps() -> None:
+    schema = Schema(SCHEMA, sort_maps=True)
+    keys = [f"key{i:02}" for i in range(50)]
+    datum = {"a": dict.fromkeys(reversed(keys), 1), "b": 2}
+
+    decoded = schema.read(schema.write(datum))
+
+    assert list(decoded) == ["b", "a"]
+    assert list(decoded["a"]) == keys
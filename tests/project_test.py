@@ -0,0 +1,69 @@
+import json
+
+import pytest
+from pyo3avro_rs import Schema
+
+SCHEMA = Schema(
+    """{
+    "type": "record", "name": "Event", "namespace": "com.acme", "fields": [
+        {"name": "id", "type": "long"},
+        {"name": "user", "type": {"type": "record", "name": "User", "fields": [
+            {"name": "name", "type": "string"},
+            {"name": "email", "type": "string"}
+        ]}},
+        {"name": "tags", "type": {"type": "array", "items": "string"}}
+    ]
+}"""
+)
+
+
+def test_project() -> None:
+    projected = SCHEMA.project(["id", "user.name"])
+
+    assert json.loads(projected.to_json()) == {
+        "type": "record",
+        "name": "Event",
+        "namespace": "com.acme",
+        "fields": [
+            {"name": "id", "type": "long"},
+            {
+                "name": "user",
+                "type": {
+                    "type": "record",
+                    "name": "User",
+                    "namespace": "com.acme",
+                    "fields": [{"name": "name", "type": "string"}],
+                },
+            },
+        ],
+    }
+    datum = {"id": 1, "user": {"name": "a", "email": "a@b.c"}, "tags": []}
+    assert projected.read(projected.write(datum)) == {"id": 1, "user": {"name": "a"}}
+
+
+def test_whole_field_wins() -> None:
+    projected = SCHEMA.project(["user.name", "user"])
+
+    datum = {"user": {"name": "a", "email": "a@b.c"}}
+    assert projected.read(projected.write(datum)) == datum
+
+
+def test_unknown_path() -> None:
+    with pytest.raises(ValueError, match="Unknown field path: user.phone"):
+        SCHEMA.project(["user.phone"])
+
+
+def test_named_type_selected_differently() -> None:
+    schema = Schema(
+        """{"type": "record", "name": "R", "fields": [
+            {"name": "a", "type": {"type": "record", "name": "S", "fields": [
+                {"name": "x", "type": "long"}, {"name": "z", "type": "long"}
+            ]}},
+            {"name": "b", "type": "S"}
+        ]}"""
+    )
+
+    with pytest.raises(ValueError, match="S is used at several paths with different fields selected"):
+        schema.project(["a.x", "b.z"])
+    projected = schema.project(["a.z", "b.z"])
+    assert projected.read(projected.write({"a": {"z": 1}, "b": {"z": 2}})) == {"a": {"z": 1}, "b": {"z": 2}}
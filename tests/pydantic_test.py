@@ -0,0 +1,58 @@
+from typing import Optional
+
+import pytest
+from pyo3avro_rs import Schema
+
+pydantic = pytest.importorskip("pydantic")
+
+
+class Address(pydantic.BaseModel):
+    city: str
+
+
+class User(pydantic.BaseModel):
+    id: int
+    email: str = pydantic.Field(alias="emailAddress")
+    score: float = 0.5
+    nickname: Optional[str] = None
+    tags: list[str] = []
+    address: Address
+
+
+def test_derived_schema_accepts_dicts() -> None:
+    schema = Schema.from_pydantic(User)
+    datum = {
+        "id": 1,
+        "emailAddress": "a@b.c",
+        "score": 0.5,
+        "nickname": None,
+        "tags": [],
+        "address": {"city": "Paris"},
+    }
+
+    expected = User(id=1, emailAddress="a@b.c", address=Address(city="Paris"))
+    assert schema.read(schema.write(datum)) == expected
+
+
+def test_round_trip() -> None:
+    schema = Schema.from_pydantic(User)
+    user = User(id=1, emailAddress="a@b.c", tags=["x"], address=Address(city="Paris"))
+
+    assert schema.read(schema.write(user)) == user
+
+
+def test_encode_with_explicit_schema() -> None:
+    schema = Schema("""{"type": "record", "name": "User", "fields": [
+        {"name": "id", "type": "long"}, {"name": "emailAddress", "type": "string"}
+    ]}""")
+    user = User(id=1, emailAddress="a@b.c", address=Address(city="Paris"))
+
+    assert schema.read(schema.write(user)) == {"id": 1, "emailAddress": "a@b.c"}
+
+
+def test_unsupported_annotation() -> None:
+    class Event(pydantic.BaseModel):
+        payload: object
+
+    with pytest.raises(TypeError, match="Unsupported annotation"):
+        Schema.from_pydantic(Event)
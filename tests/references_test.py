@@ -0,0 +1,66 @@
+import enum
+import json
+
+import pytest
+from pyo3avro_rs import Schema
+
+SCHEMA = Schema(
+    """{
+    "type": "record", "name": "Reading", "namespace": "com.acme", "fields": [
+        {"name": "unit", "type": {"type": "enum", "name": "Unit", "symbols": ["C", "F"]}},
+        {"name": "id", "type": {"type": "fixed", "name": "Id", "size": 2}},
+        {"name": "units", "type": {"type": "array", "items": "Unit"}},
+        {"name": "ids", "type": {"type": "map", "values": "com.acme.Id"}},
+        {"name": "other_unit", "type": ["null", "Unit"]},
+        {"name": "other_id", "type": ["null", "Id"]}
+    ]
+}"""
+)
+
+
+def test_round_trip() -> None:
+    datum = {
+        "unit": "C",
+        "id": b"ab",
+        "units": ["F", "C"],
+        "ids": {"x": b"cd"},
+        "other_unit": "F",
+        "other_id": b"ef",
+    }
+
+    assert SCHEMA.read(SCHEMA.write(datum)) == datum
+
+
+def test_to_json_defines_types_once() -> None:
+    fields = json.loads(SCHEMA.to_json())["fields"]
+
+    assert fields[0]["type"] == {"type": "enum", "name": "Unit", "namespace": "com.acme", "symbols": ["C", "F"]}
+    assert fields[2]["type"] == {"type": "array", "items": "com.acme.Unit"}
+    assert fields[3]["type"] == {"type": "map", "values": "com.acme.Id"}
+    assert fields[4]["type"] == ["null", "com.acme.Unit"]
+
+
+def test_to_json_round_trip() -> None:
+    class Unit(enum.Enum):
+        C = "C"
+        F = "F"
+
+    schema = Schema(SCHEMA.to_json())
+    schema.register_types({"com.acme.Unit": Unit})
+
+    assert schema.to_json() == SCHEMA.to_json()
+    assert schema.fingerprint == SCHEMA.fingerprint
+    datum = {"unit": "C", "id": b"ab", "units": [], "ids": {}, "other_unit": None, "other_id": None}
+    assert schema.read(schema.write(datum))["unit"] is Unit.C
+
+
+def test_referenced_enum_checked() -> None:
+    datum = {"unit": "C", "id": b"ab", "units": ["K"], "ids": {}, "other_unit": None, "other_id": None}
+
+    with pytest.raises(TypeError):
+        SCHEMA.write(datum)
+
+
+def test_unsupported_type_not_encoded_as_null() -> None:
+    with pytest.raises(TypeError, match="Encoding time-millis values is not supported"):
+        Schema('{"type": "int", "logicalType": "time-millis"}').write(1)
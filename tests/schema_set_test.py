@@ -0,0 +1,46 @@
+import struct
+
+import pytest
+from pyo3avro_rs import Schema, SchemaSet
+
+V1 = Schema('{"type": "record", "name": "Order", "fields": [{"name": "id", "type": "long"}]}')
+V2 = Schema(
+    '{"type": "record", "name": "Order", "fields": ['
+    '{"name": "id", "type": "long"}, {"name": "note", "type": "string", "default": ""}]}'
+)
+
+
+def test_read_resolves_to_newest() -> None:
+    versions = SchemaSet([V1, V2])
+
+    old = b"\xc3\x01" + V1.fingerprint + V1.write({"id": 1})
+    new = b"\xc3\x01" + V2.fingerprint + V2.write({"id": 2, "note": "rush"})
+
+    assert versions.read(old) == {"id": 1, "note": ""}
+    assert versions.read(new) == {"id": 2, "note": "rush"}
+
+
+def test_read_registry_framed() -> None:
+    versions = SchemaSet()
+    versions.add(V1, registry_id=7)
+    versions.add(V2, registry_id=8)
+
+    message = b"\x00" + struct.pack(">I", 7) + V1.write({"id": 3})
+
+    assert versions.read(message) == {"id": 3, "note": ""}
+
+
+def test_unknown_writer() -> None:
+    versions = SchemaSet([V2])
+
+    with pytest.raises(ValueError, match="Unknown writer schema with fingerprint"):
+        versions.read(b"\xc3\x01" + V1.fingerprint + V1.write({"id": 1}))
+
+
+def test_read_with_decode_errors() -> None:
+    newest = Schema(V2.to_json(), decode_errors="replace")
+    versions = SchemaSet([V2, newest])
+
+    message = b"\xc3\x01" + V2.fingerprint + b"\x02\x0aab\xffcd"
+
+    assert versions.read(message) == {"id": 1, "note": "ab�cd"}
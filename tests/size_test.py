@@ -0,0 +1,52 @@
+import sys
+
+import pytest
+from pyo3avro_rs import Schema
+
+SCHEMA = """{
+    "type": "record", "name": "Batch", "fields": [
+        {"name": "ids", "type": {"type": "array", "items": "long"}},
+        {"name": "labels", "type": {"type": "map", "values": "string"}}
+    ]
+}"""
+
+
+def deep_size(value: object) -> int:
+    size = sys.getsizeof(value)
+    if isinstance(value, dict):
+        size += sum(sys.getsizeof(k) + deep_size(v) for k, v in value.items())
+    elif isinstance(value, list):
+        size += sum(deep_size(item) for item in value)
+    return size
+
+
+def test_estimate_decoded_size() -> None:
+    schema = Schema(SCHEMA)
+    datum = {"ids": list(range(1000, 2000)), "labels": {f"k{i}": "v" * i for i in range(50)}}
+    encoded = schema.write(datum)
+
+    estimate = schema.estimate_decoded_size(encoded)
+
+    assert estimate > 10 * len(encoded)
+    assert 0.8 < estimate / deep_size(schema.read(encoded)) < 1.2
+
+
+@pytest.mark.parametrize(
+    "schema, datum",
+    [
+        ('"long"', -(2**40)),
+        ('{"type": "array", "items": "string"}', []),
+        ('{"type": "map", "values": ["null", "double"]}', {"a": None, "b": 1.5}),
+        (
+            '{"type": "record", "name": "R", "fields": ['
+            '{"name": "e", "type": {"type": "enum", "name": "E", "symbols": ["A", "B"]}},'
+            '{"name": "f", "type": {"type": "fixed", "name": "F", "size": 3}},'
+            '{"name": "items", "type": {"type": "array", "items": "int"}}]}',
+            {"e": "B", "f": b"abc", "items": list(range(200))},
+        ),
+    ],
+)
+def test_encoded_size(schema: str, datum: object) -> None:
+    schema = Schema(schema)
+
+    assert schema.encoded_size(datum) == len(schema.write(datum))
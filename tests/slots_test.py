@@ -0,0 +1,30 @@
+import sys
+
+import pytest
+from pyo3avro_rs import Schema
+
+SCHEMA = """{
+    "type": "record", "name": "Row", "fields": [
+        {"name": "id", "type": "long"},
+        {"name": "class", "type": "string"},
+        {"name": "child", "type": {"type": "record", "name": "Child", "fields": [{"name": "x", "type": "int"}]}}
+    ]
+}"""
+
+
+def test_slots_records() -> None:
+    schema = Schema(SCHEMA, records="slots")
+    datum = {"id": 1, "class": "a", "child": {"x": 2}}
+
+    row = schema.read(schema.write(datum))
+
+    assert not hasattr(row, "__dict__")
+    assert (row.id, getattr(row, "class"), row.child.x) == (1, "a", 2)
+    assert repr(row) == "Row(id=1, class='a', child=Child(x=2))"
+    assert sys.getsizeof(row) < sys.getsizeof(datum)
+    assert schema.read(schema.write(row)) == row
+
+
+def test_unknown_records_mode() -> None:
+    with pytest.raises(ValueError, match="Unknown records mode: tuple"):
+        Schema(SCHEMA, records="tuple")
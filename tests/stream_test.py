@@ -0,0 +1,44 @@
+import pytest
+from pyo3avro_rs import Schema
+
+SCHEMA = Schema('{"type": "record", "name": "R", "fields": [{"name": "id", "type": "long"}]}')
+LONG = Schema('"long"')
+DATUMS = [{"id": 1}, {"id": 300}, {"id": -2}]
+
+
+def test_back_to_back() -> None:
+    assert SCHEMA.read_stream(b"".join(SCHEMA.write(datum) for datum in DATUMS)) == DATUMS
+
+
+def test_count_prefix() -> None:
+    buffer = LONG.write(2) + b"".join(SCHEMA.write(datum) for datum in DATUMS)
+
+    assert SCHEMA.read_stream(buffer, prefix="count") == DATUMS[:2]
+
+
+def test_length_prefix() -> None:
+    buffer = b"".join(LONG.write(len(SCHEMA.write(datum))) + SCHEMA.write(datum) for datum in DATUMS)
+
+    assert SCHEMA.read_stream(buffer, prefix="length") == DATUMS
+
+
+def test_truncated() -> None:
+    with pytest.raises(ValueError, match="Datum 1 is truncated"):
+        SCHEMA.read_stream(b"\x02\x02\x04\x02", prefix="length")
+    with pytest.raises(ValueError, match="Failed to decode datum 1"):
+        SCHEMA.read_stream(LONG.write(2) + SCHEMA.write(DATUMS[0]), prefix="count")
+
+
+def test_read_iter() -> None:
+    buffer = b"".join(SCHEMA.write(datum) for datum in DATUMS)
+
+    assert list(SCHEMA.read_iter(buffer)) == DATUMS
+    assert list(SCHEMA.read_iter(LONG.write(2) + buffer, prefix="count")) == DATUMS[:2]
+
+
+def test_read_iter_stops_early() -> None:
+    datums = SCHEMA.read_iter(SCHEMA.write(DATUMS[1]) + b"\x80")
+
+    assert next(datums) == DATUMS[1]
+    with pytest.raises(ValueError, match="Failed to decode datum 1"):
+        next(datums)
@@ -0,0 +1,38 @@
+import pytest
+from pyo3avro_rs import Schema
+
+STRING = '"string"'
+RECORD = '{"type": "record", "name": "R", "fields": [{"name": "s", "type": ["null", "string"]}]}'
+INVALID = b"\x0aab\xffcd"  # 5 bytes string, not valid UTF-8
+
+
+def test_lone_surrogate_rejected() -> None:
+    with pytest.raises(UnicodeEncodeError):
+        Schema(STRING).write("a\udcffb")
+
+
+@pytest.mark.parametrize("errors, expected", [("replace", "a?b"), ("ignore", "ab")])
+def test_lone_surrogate_handled(errors: str, expected: str) -> None:
+    schema = Schema(STRING, encode_errors=errors)
+
+    assert schema.read(schema.write("a\udcffb")) == expected
+
+
+def test_invalid_utf8_rejected() -> None:
+    with pytest.raises(ValueError):
+        Schema(STRING).read(INVALID)
+
+
+@pytest.mark.parametrize(
+    "errors, expected",
+    [("replace", "ab�cd"), ("surrogateescape", "ab\udcffcd"), ("bytes", b"ab\xffcd")],
+)
+def test_invalid_utf8_handled(errors: str, expected: object) -> None:
+    assert Schema(STRING, decode_errors=errors).read(INVALID) == expected
+
+
+def test_invalid_utf8_in_union() -> None:
+    schema = Schema(RECORD, decode_errors="replace")
+
+    assert schema.read(b"\x02" + INVALID) == {"s": "ab�cd"}
+    assert schema.read(schema.write({"s": "ok"})) == {"s": "ok"}
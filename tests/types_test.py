@@ -0,0 +1,90 @@
+import gc
+import weakref
+from dataclasses import dataclass
+
+import pytest
+from pyo3avro_rs import Schema
+
+SCHEMA = """[
+    "null",
+    {"type": "record", "name": "OrderCreated", "namespace": "com.acme", "fields": [
+        {"name": "id", "type": "long"},
+        {"name": "items", "type": {"type": "array", "items": {
+            "type": "record", "name": "Item", "namespace": "com.acme", "fields": [
+                {"name": "sku", "type": "string"}
+            ]
+        }}}
+    ]}
+]"""
+
+
+@dataclass
+class Item:
+    sku: str
+
+
+@dataclass
+class OrderCreated:
+    id: int
+    items: list
+
+
+def test_round_trip() -> None:
+    schema = Schema(SCHEMA)
+    schema.register_types({"com.acme.OrderCreated": OrderCreated, "com.acme.Item": Item})
+    order = OrderCreated(id=1, items=[Item(sku="a"), Item(sku="b")])
+
+    assert schema.read(schema.write(order)) == order
+    assert schema.read(schema.write(None)) is None
+
+
+def test_dicts_still_accepted() -> None:
+    schema = Schema(SCHEMA)
+    schema.register_types({"com.acme.Item": Item})
+
+    decoded = schema.read(schema.write({"id": 1, "items": [{"sku": "a"}]}))
+
+    assert decoded == {"id": 1, "items": [Item(sku="a")]}
+
+
+def test_unknown_record_type() -> None:
+    schema = Schema(SCHEMA)
+
+    with pytest.raises(ValueError):
+        schema.register_types({"com.acme.OrderCancelled": OrderCreated})
+
+
+def test_record_keys_are_shared() -> None:
+    schema = Schema('{"type": "record", "name": "Point", "fields": [{"name": "x", "type": "int"}]}')
+
+    first, second = schema.read(schema.write({"x": 1})), schema.read(schema.write({"x": 2}))
+
+    assert next(iter(first)) is next(iter(second))
+
+
+def test_unregistered_types_released() -> None:
+    schema = Schema('{"type": "record", "name": "Point", "fields": [{"name": "x", "type": "int"}]}')
+    classes = []
+    for i in range(1000):
+        cls = type(f"Point{i}", (), {})
+        classes.append(weakref.ref(cls))
+        with pytest.raises(TypeError):
+            schema.write(cls())
+        del cls
+    gc.collect()
+
+    assert classes[0]() is None
+
+
+def test_record_name_defined_twice() -> None:
+    schema = Schema(
+        """{"type": "record", "name": "R", "fields": [
+            {"name": "a", "type": {"type": "record", "name": "S", "fields": [{"name": "x", "type": "long"}]}},
+            {"name": "b", "type": {"type": "record", "name": "S", "fields": [{"name": "z", "type": "long"}]}}
+        ]}"""
+    )
+    datum = {"a": {"x": 1}, "b": {"z": 2}}
+
+    assert schema.read(schema.write(datum)) == datum
+    with pytest.raises(ValueError, match="S has several different definitions"):
+        schema.to_json()
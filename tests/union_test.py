@@ -0,0 +1,115 @@
+import pytest
+from pyo3avro_rs import Schema, SerializationError
+
+SCHEMA = Schema(
+    """[
+        "null",
+        {"type": "record", "name": "Order", "namespace": "com.acme", "fields": [
+            {"name": "id", "type": "long"}
+        ]},
+        "string"
+    ]"""
+)
+
+
+def test_read_union() -> None:
+    assert SCHEMA.read_union(SCHEMA.write({"id": 1})) == ("com.acme.Order", {"id": 1})
+    assert SCHEMA.read_union(SCHEMA.write("text")) == ("string", "text")
+    assert SCHEMA.read_union(SCHEMA.write(None)) == ("null", None)
+
+
+def test_write_branch() -> None:
+    payload = SCHEMA.write({"id": 1}, branch="com.acme.Order")
+
+    assert payload == SCHEMA.write({"id": 1})
+    assert SCHEMA.read(payload) == {"id": 1}
+
+
+def test_write_unknown_branch() -> None:
+    with pytest.raises(ValueError):
+        SCHEMA.write("text", branch="com.acme.Unknown")
+
+
+def test_not_a_union() -> None:
+    schema = Schema('"string"')
+
+    with pytest.raises(ValueError):
+        schema.write("text", branch="string")
+    with pytest.raises(ValueError):
+        schema.read_union(schema.write("text"))
+
+
+@pytest.mark.parametrize("union", ['["int", "long"]', '["long", "int"]', '["null", "long", "double", "int"]'])
+def test_integer_promotion(union: str) -> None:
+    schema = Schema(union)
+
+    assert schema.read_union(schema.write(1)) == ("int", 1)
+    assert schema.read_union(schema.write(1 << 40)) == ("long", 1 << 40)
+    assert schema.read_union(schema.write(-(1 << 31) - 1)) == ("long", -(1 << 31) - 1)
+
+
+def test_no_branch_chains_cause() -> None:
+    schema = Schema('["null", {"type": "record", "name": "User", "fields": [{"name": "email", "type": "string"}]}]')
+
+    def broken(value: str) -> str:
+        raise RuntimeError("hook failed")
+
+    schema.register_hooks(encode={"email": broken})
+
+    with pytest.raises(SerializationError, match="No union branch accepts the datum") as error:
+        schema.write({"email": "a@b.c"})
+
+    assert isinstance(error.value.__cause__, RuntimeError)
+    assert str(error.value.__cause__) == "hook failed"
+
+
+def test_nested_union_chains_cause() -> None:
+    schema = Schema(
+        """["null", {"type": "record", "name": "Outer", "fields": [
+            {"name": "inner", "type": ["null", {"type": "record", "name": "Inner", "fields": [
+                {"name": "x", "type": "string"}
+            ]}]}
+        ]}]"""
+    )
+
+    def broken(value: str) -> str:
+        raise RuntimeError("hook failed")
+
+    schema.register_hooks(encode={"inner.x": broken})
+
+    with pytest.raises(SerializationError, match="No union branch accepts the datum") as error:
+        schema.write({"inner": {"x": "a"}})
+
+    assert isinstance(error.value.__cause__, RuntimeError)
+
+
+def test_no_branch_type_mismatch() -> None:
+    with pytest.raises(SerializationError) as error:
+        Schema('["null", "long"]').write("text")
+
+    assert isinstance(error.value, TypeError)
+    assert error.value.__cause__ is None
+
+
+def test_unions_as_tuples() -> None:
+    schema = Schema(
+        """{"type": "record", "name": "Event", "fields": [
+            {"name": "payload", "type": [
+                "null",
+                "long",
+                {"type": "record", "name": "Click", "fields": [{"name": "x", "type": "long"}]}
+            ]}
+        ]}""",
+        unions="tuple",
+    )
+
+    assert schema.read(schema.write({"payload": {"x": 1}})) == {"payload": ("Click", {"x": 1})}
+    assert schema.read(schema.write({"payload": ("Click", {"x": 1})})) == {"payload": ("Click", {"x": 1})}
+    assert schema.read(schema.write({"payload": ("long", 2)})) == {"payload": ("long", 2)}
+    assert schema.read(schema.write({"payload": 2})) == {"payload": ("long", 2)}
+    assert schema.read(schema.write({"payload": None})) == {"payload": ("null", None)}
+
+
+def test_unknown_unions_mode() -> None:
+    with pytest.raises(ValueError, match="Unknown unions mode: dict"):
+        Schema('["null", "long"]', unions="dict")
@@ -0,0 +1,57 @@
+import datetime
+import decimal
+import uuid
+
+import pytest
+from pyo3avro_rs import Datum, Schema
+
+DURATION = '{"type": {"type": "fixed", "name": "Span", "size": 12}, "logicalType": "duration"}'
+
+
+@pytest.mark.parametrize(
+    "schema, datum, expected",
+    [
+        ('{"type": "int", "logicalType": "time-millis"}', 45_296_789, datetime.time(12, 34, 56, 789000)),
+        ('{"type": "long", "logicalType": "time-micros"}', 45_296_000_001, datetime.time(12, 34, 56, 1)),
+        (
+            '{"type": "bytes", "logicalType": "decimal", "precision": 6, "scale": 2}',
+            b"\xff\x85",
+            decimal.Decimal("-1.23"),
+        ),
+    ],
+)
+def test_logical_types(schema: str, datum: object, expected: object) -> None:
+    underlying = Schema(schema.replace("logicalType", "comment"))
+
+    assert Schema(schema).read(underlying.write(datum)) == expected
+
+
+def test_uuid() -> None:
+    value = uuid.uuid4()
+    schema = Schema('{"type": "string", "logicalType": "uuid"}')
+
+    assert schema.read(Schema('"string"').write(str(value))) == value
+
+
+def test_unsupported_warn() -> None:
+    raw = Schema('{"type": "fixed", "name": "Span", "size": 12}').write(bytes(12))
+
+    with pytest.warns(RuntimeWarning, match="Unsupported duration value"):
+        assert Schema(DURATION).read(raw) is None
+
+
+def test_unsupported_raise() -> None:
+    raw = Schema('{"type": "fixed", "name": "Span", "size": 12}').write(bytes(12))
+
+    with pytest.raises(TypeError, match="Unsupported duration value"):
+        Schema(DURATION, unsupported="raise").read(raw)
+
+
+def test_unsupported_datum() -> None:
+    raw = Schema('{"type": "fixed", "name": "Span", "size": 12}').write(bytes(range(12)))
+
+    datum = Schema(DURATION, unsupported="datum").read(raw)
+
+    assert isinstance(datum, Datum)
+    assert datum.kind == "duration"
+    assert datum.raw == bytes(range(12))